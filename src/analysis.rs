@@ -0,0 +1,587 @@
+//! The analysis pipeline: load sources, build mappings, compute tracking.
+
+use crate::config::Config;
+use crate::coverage::Coverage;
+use crate::extractors::{
+    extract_registries_from, parse_java_sources, parse_rust_sources, parse_rust_structs,
+};
+use crate::fallback;
+use crate::mappings::MethodTable;
+use crate::matching::{MatchReview, NameMatcher};
+use crate::provider::SourceProvider;
+use crate::registry_parser::{
+    self, ClassMapping, ClassesJson, MappingConflict, RegistrationGaps, RegistryMismatch,
+};
+use crate::{
+    AnalysisResult, ClaimConflict, ClassMethods, ClassTracking, Error, ImplementationStatus,
+    MatchSource, MethodTracking, RegistryEntryReport, RustStruct, SecondaryBehavior,
+};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Everything parsed from yarn and SteelMC
+pub struct Sources {
+    pub java_classes: Vec<ClassMethods>,
+    pub rust_classes: Vec<ClassMethods>,
+    /// Registry id -> vanilla class, from Steel's classes.json when built, otherwise from yarn
+    pub classes_json: ClassesJson,
+    /// Registry id -> vanilla class derived from yarn's registration classes
+    pub yarn_registries: ClassesJson,
+    /// Differences between classes.json and yarn, when classes.json exists
+    pub registry_mismatches: Option<Vec<RegistryMismatch>>,
+    /// Registry id -> Steel behavior from the generated registration code
+    pub steel_blocks: HashMap<String, String>,
+    pub steel_items: HashMap<String, String>,
+    /// Every struct under the Steel folders, including those without methods
+    pub rust_structs: Vec<RustStruct>,
+    /// Generated registration files the Steel provider lacked, read from the working tree
+    pub generated_from_working_tree: Vec<String>,
+}
+
+/// How vanilla classes relate to Steel behaviors through the registries
+pub struct Mappings {
+    pub block_classes: ClassMapping,
+    pub item_classes: ClassMapping,
+    pub conflicts: Vec<MappingConflict>,
+    pub registration_gaps: RegistrationGaps,
+    pub registry_usage: HashMap<String, usize>,
+    pub generic_behaviors: HashSet<String>,
+}
+
+/// Run the whole pipeline
+pub fn analyze(config: &Config) -> Result<AnalysisResult, Error> {
+    let sources = load_sources(config)?;
+    let mappings = build_mappings(config, &sources);
+    Ok(compute_tracking(config, &sources, &mappings))
+}
+
+/// Steel's generated registration code, relative to `steel-core`
+pub const GENERATED_BLOCKS_PATH: &str = "src/behavior/generated/blocks.rs";
+pub const GENERATED_ITEMS_PATH: &str = "src/behavior/generated/items.rs";
+
+pub fn load_sources(config: &Config) -> Result<Sources, Error> {
+    let mut sources = load_yarn_sources(config)?;
+    load_steel_sources(config, config.steel_provider()?.as_ref(), &mut sources);
+    Ok(sources)
+}
+
+/// The vanilla side of `Sources`, with the Steel side left empty
+pub fn load_yarn_sources(config: &Config) -> Result<Sources, Error> {
+    let yarn = config.yarn_provider()?;
+
+    // Registry id -> vanilla class, straight from yarn's registration classes
+    let yarn_registries = extract_registries_from(yarn.as_ref());
+
+    // Prefer Steel's classes.json when it has been built, cross-checked against yarn
+    let (classes_json, registry_mismatches) =
+        if let Some((origin, content)) = config.read_classes_json()? {
+            let classes_json =
+                ClassesJson::from_source(&content).map_err(|e| Error::load(&origin, e.into()))?;
+            let mismatches = registry_parser::cross_check(&classes_json, &yarn_registries);
+            (classes_json, Some(mismatches))
+        } else {
+            (yarn_registries.clone(), None)
+        };
+
+    let java_classes = parse_yarn_classes(config, yarn.as_ref());
+    if java_classes.is_empty() {
+        return Err(Error::MissingSource(format!(
+            "no Java classes under {}",
+            config.yarn_src
+        )));
+    }
+
+    Ok(Sources {
+        java_classes,
+        rust_classes: Vec::new(),
+        rust_structs: Vec::new(),
+        classes_json,
+        yarn_registries,
+        registry_mismatches,
+        steel_blocks: HashMap::new(),
+        steel_items: HashMap::new(),
+        generated_from_working_tree: Vec::new(),
+    })
+}
+
+/// Vanilla classes under the configured folders, categorized by the package they are declared in
+pub fn parse_yarn_classes(config: &Config, yarn: &dyn SourceProvider) -> Vec<ClassMethods> {
+    let mut java_classes = Vec::new();
+    for folder in &config.java_folders {
+        let files = yarn.files(&format!("net/minecraft/{folder}"), "java");
+        for mut c in parse_java_sources(files, &config.java_options) {
+            c.class_type = config.package_rules.classify(&c.package).to_string();
+            java_classes.push(c);
+        }
+    }
+    java_classes
+}
+
+/// Replace the Steel side of `sources` with what `steel` holds.
+///
+/// The generated registration code is a build output and usually not committed, so it falls
+/// back to the working tree under `config.steel_core` when `steel` lacks it. Such files are
+/// listed in `sources.generated_from_working_tree`.
+pub fn load_steel_sources(config: &Config, steel: &dyn SourceProvider, sources: &mut Sources) {
+    sources.generated_from_working_tree.clear();
+    let mut read = |path: &str| {
+        steel.read(path).unwrap_or_else(|| {
+            let working_tree = fs::read_to_string(Path::new(&config.steel_core).join(path));
+            if working_tree.is_ok() {
+                sources.generated_from_working_tree.push(path.to_string());
+            }
+            working_tree.unwrap_or_default()
+        })
+    };
+    let blocks = read(GENERATED_BLOCKS_PATH);
+    let items = read(GENERATED_ITEMS_PATH);
+    sources.steel_blocks = registry_parser::generated_blocks_from_source(&blocks);
+    sources.steel_items = registry_parser::generated_items_from_source(&items);
+
+    sources.rust_classes.clear();
+    sources.rust_structs.clear();
+    for (folder, category) in &config.rust_folders {
+        let files = steel.files(&format!("src/{folder}"), "rs");
+        for mut s in parse_rust_structs(files.iter().map(|(path, content)| (path, content))) {
+            s.category = category.clone();
+            sources.rust_structs.push(s);
+        }
+        for mut c in parse_rust_sources(files) {
+            c.class_type = category.clone();
+            sources.rust_classes.push(c);
+        }
+    }
+}
+
+pub fn build_mappings(config: &Config, sources: &Sources) -> Mappings {
+    // Build vanilla_class -> steel_behaviors mapping
+    let (block_classes, item_classes) = registry_parser::build_class_mapping(
+        &sources.classes_json,
+        &sources.steel_blocks,
+        &sources.steel_items,
+    );
+
+    Mappings {
+        conflicts: registry_parser::find_mapping_conflicts(&block_classes, &item_classes),
+        registration_gaps: registry_parser::find_registration_gaps(
+            &sources.classes_json,
+            &sources.steel_blocks,
+            &sources.steel_items,
+        ),
+        registry_usage: registry_parser::registry_usage_counts(&sources.classes_json),
+        generic_behaviors: fallback::detect_generic_behaviors(
+            &config.fallback,
+            &[&block_classes, &item_classes],
+            &sources
+                .java_classes
+                .iter()
+                .filter_map(|c| Some((c.class_name.as_str(), c.superclass.as_deref()?)))
+                .collect(),
+        ),
+        block_classes,
+        item_classes,
+    }
+}
+
+/// A Steel type explicitly claiming a vanilla class
+#[derive(Debug, Clone, Copy)]
+struct Claim<'a> {
+    steel_type: &'a str,
+    /// `None` for a marked struct without methods
+    class: Option<&'a ClassMethods>,
+}
+
+/// Steel types by lowercase name, plus the vanilla classes they explicitly claim
+struct RustIndex<'a> {
+    by_name: HashMap<String, &'a ClassMethods>,
+    explicit: HashMap<&'a str, Claim<'a>>,
+    /// Vanilla classes claimed by more than one Steel type
+    claim_conflicts: Vec<ClaimConflict>,
+}
+
+impl<'a> RustIndex<'a> {
+    fn new(rust_classes: &'a [ClassMethods], rust_structs: &'a [RustStruct]) -> Self {
+        let by_name: HashMap<String, &ClassMethods> = rust_classes
+            .iter()
+            .map(|c| (c.class_name.to_lowercase(), c))
+            .collect();
+
+        // Explicit markers, on the impl or on the struct, are authoritative over inferred
+        // matches
+        let impl_claims = rust_classes.iter().flat_map(|c| {
+            c.vanilla_classes.iter().map(move |v| {
+                let claim = Claim {
+                    steel_type: c.class_name.as_str(),
+                    class: Some(c),
+                };
+                (v.as_str(), claim)
+            })
+        });
+        let struct_claims = rust_structs.iter().flat_map(|s| {
+            let claim = Claim {
+                steel_type: s.name.as_str(),
+                class: by_name.get(&s.name.to_lowercase()).copied(),
+            };
+            s.vanilla_classes.iter().map(move |v| (v.as_str(), claim))
+        });
+        let mut claims: HashMap<&str, Vec<Claim>> = HashMap::new();
+        for (vanilla_class, claim) in impl_claims.chain(struct_claims) {
+            claims.entry(vanilla_class).or_default().push(claim);
+        }
+
+        // A class claimed twice goes to the alphabetically first claimant, so the choice does
+        // not depend on extraction order
+        let mut explicit = HashMap::new();
+        let mut claim_conflicts = Vec::new();
+        for (vanilla_class, mut claimants) in claims {
+            claimants.sort_by(|a, b| a.steel_type.cmp(b.steel_type));
+            claimants.dedup_by(|a, b| a.steel_type == b.steel_type);
+            if claimants.len() > 1 {
+                claim_conflicts.push(ClaimConflict {
+                    vanilla_class: vanilla_class.to_string(),
+                    steel_types: claimants.iter().map(|c| c.steel_type.to_string()).collect(),
+                    chosen: claimants[0].steel_type.to_string(),
+                });
+            }
+            explicit.insert(vanilla_class, claimants[0]);
+        }
+        claim_conflicts.sort_by(|a, b| a.vanilla_class.cmp(&b.vanilla_class));
+
+        Self {
+            by_name,
+            explicit,
+            claim_conflicts,
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<&'a ClassMethods> {
+        self.by_name.get(&name.to_lowercase()).copied()
+    }
+}
+
+pub fn compute_tracking(config: &Config, sources: &Sources, mappings: &Mappings) -> AnalysisResult {
+    let rust_index = RustIndex::new(&sources.rust_classes, &sources.rust_structs);
+    let name_matcher = NameMatcher::new(
+        &config.matcher,
+        sources.rust_classes.iter().map(|c| c.class_name.as_str()),
+    );
+    let mut match_reviews: Vec<MatchReview> = Vec::new();
+    let mut tracking: Vec<ClassTracking> = Vec::new();
+
+    for java_class in &sources.java_classes {
+        if !java_class.is_real_class || java_class.methods.is_empty() {
+            continue;
+        }
+
+        // Get method mapping based on type, categories without a table are not tracked
+        let Some(method_map) = config.method_tables.for_category(&java_class.class_type) else {
+            continue;
+        };
+
+        // Only include classes that have at least one tracked method
+        let has_tracked_method = java_class
+            .methods
+            .iter()
+            .any(|m| method_map.contains_key(m.as_str()));
+        if !has_tracked_method {
+            continue;
+        }
+
+        // Find corresponding Rust class
+        let bindings = match java_class.class_type.as_str() {
+            "block" => mappings.block_classes.get(&java_class.class_name),
+            "item" => mappings.item_classes.get(&java_class.class_name),
+            _ => None,
+        }
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+        let steel_behavior = bindings.first().map(|b| b.steel_behavior.as_str());
+        let is_registry_class = java_class.class_type == "block" || java_class.class_type == "item";
+        let explicit = rust_index
+            .explicit
+            .get(java_class.class_name.as_str())
+            .copied();
+
+        // Fuzzy name matching for entities/goals, low-confidence candidates go to review
+        let mut name_match = None;
+        if !is_registry_class && explicit.is_none() {
+            match name_matcher.best_match(&java_class.class_name) {
+                Some(candidate) if name_matcher.is_accepted(&candidate) => {
+                    name_match = Some(candidate)
+                }
+                Some(candidate) => match_reviews.push(MatchReview {
+                    java_class: java_class.class_name.clone(),
+                    class_type: java_class.class_type.clone(),
+                    candidate,
+                }),
+                None => {}
+            }
+        }
+
+        let (rust_class, rust_name, class_matched_by) = match explicit {
+            Some(claim) => (
+                claim.class,
+                Some(claim.steel_type),
+                Some(MatchSource::Explicit),
+            ),
+            None => {
+                let inferred = if is_registry_class {
+                    steel_behavior.and_then(|steel| rust_index.get(steel))
+                } else {
+                    name_match
+                        .as_ref()
+                        .and_then(|m| rust_index.get(&m.rust_class))
+                };
+                (
+                    inferred,
+                    inferred.map(|r| r.class_name.as_str()),
+                    inferred.map(|_| MatchSource::Inferred),
+                )
+            }
+        };
+        let generic_fallback = steel_behavior
+            .filter(|steel| {
+                fallback::is_generic_fallback(
+                    &mappings.generic_behaviors,
+                    steel,
+                    &java_class.class_name,
+                )
+            })
+            .map(str::to_string);
+
+        let method_tracking = track_methods(java_class, method_map, rust_class, config);
+        let tracked_against = rust_name.or(steel_behavior);
+        let secondary_behaviors = bindings
+            .iter()
+            .filter(|b| !tracked_against.is_some_and(|t| t.eq_ignore_ascii_case(&b.steel_behavior)))
+            .map(|b| {
+                let methods = track_methods(
+                    java_class,
+                    method_map,
+                    rust_index.get(&b.steel_behavior),
+                    config,
+                );
+                SecondaryBehavior {
+                    steel_behavior: b.steel_behavior.clone(),
+                    registry_ids: b.registry_ids.clone(),
+                    percentage_implemented: Coverage::raw(&methods).percentage(),
+                    methods,
+                }
+            })
+            .collect();
+
+        tracking.push(ClassTracking {
+            class_name: java_class.class_name.clone(),
+            class_type: java_class.class_type.clone(),
+            percentage_implemented: Coverage::raw(&method_tracking).percentage(),
+            weighted_percentage_implemented: Coverage::weighted(&method_tracking).percentage(),
+            methods: method_tracking,
+            registry_usage: mappings
+                .registry_usage
+                .get(&java_class.class_name)
+                .copied()
+                .unwrap_or(0),
+            generic_fallback,
+            rust_class: rust_name.map(str::to_string),
+            class_matched_by,
+            name_match,
+            secondary_behaviors,
+        });
+    }
+
+    tracking.sort_by(|a, b| a.class_name.cmp(&b.class_name));
+    match_reviews.sort_by(|a, b| a.java_class.cmp(&b.java_class));
+
+    AnalysisResult {
+        classes: tracking,
+        match_reviews,
+        claim_conflicts: rust_index.claim_conflicts,
+    }
+}
+
+/// Parity of every block and item registry entry, judged against its own Steel behavior
+pub fn registry_report(config: &Config, sources: &Sources) -> Vec<RegistryEntryReport> {
+    let rust_index = RustIndex::new(&sources.rust_classes, &sources.rust_structs);
+    let java_by_name: HashMap<&str, &ClassMethods> = sources
+        .java_classes
+        .iter()
+        .map(|c| (c.class_name.as_str(), c))
+        .collect();
+
+    let classes_json = &sources.classes_json;
+    let entries = classes_json
+        .blocks
+        .iter()
+        .map(|b| {
+            (
+                "block",
+                &b.name,
+                &b.class,
+                sources.steel_blocks.get(&b.name),
+            )
+        })
+        .chain(
+            classes_json
+                .items
+                .iter()
+                .map(|i| ("item", &i.name, &i.class, sources.steel_items.get(&i.name))),
+        );
+
+    let mut report: Vec<RegistryEntryReport> = Vec::new();
+    for (kind, name, vanilla_class, steel_behavior) in entries {
+        let methods = java_by_name
+            .get(vanilla_class.as_str())
+            .zip(config.method_tables.for_category(kind))
+            .map(|(java_class, method_map)| {
+                let rust_class = steel_behavior.and_then(|steel| rust_index.get(steel));
+                track_methods(java_class, method_map, rust_class, config)
+            })
+            .unwrap_or_default();
+        report.push(RegistryEntryReport {
+            registry_id: registry_parser::namespaced(name),
+            kind: kind.to_string(),
+            vanilla_class: vanilla_class.clone(),
+            steel_behavior: steel_behavior.cloned(),
+            registered: steel_behavior.is_some(),
+            percentage_implemented: (!methods.is_empty())
+                .then(|| Coverage::raw(&methods).percentage()),
+            methods,
+        });
+    }
+    report.sort_by(|a, b| (&a.registry_id, &a.kind).cmp(&(&b.registry_id, &b.kind)));
+    report
+}
+
+/// Track implementation status of the methods of `java_class` present in `method_map`
+fn track_methods(
+    java_class: &ClassMethods,
+    method_map: &MethodTable,
+    rust_class: Option<&ClassMethods>,
+    config: &Config,
+) -> Vec<MethodTracking> {
+    let rust_methods: &[String] = rust_class.map(|r| r.methods.as_slice()).unwrap_or_default();
+    let mut method_tracking = Vec::new();
+    for java_method in &java_class.methods {
+        // Only track methods that are in our mapping
+        if let Some(rust_equiv_opt) = method_map.get(java_method) {
+            let ignore_reason = config.ignore.lookup(&java_class.class_name, java_method);
+            let explicit = rust_class.is_some_and(|r| r.vanilla_methods.contains_key(java_method));
+            let mut rust_fn = None;
+            let (status, matched_by) = if ignore_reason.is_some() {
+                // Intentionally omitted from Steel
+                (ImplementationStatus::Ignored, None)
+            } else if explicit {
+                // Steel marks a function as implementing this method
+                rust_fn = rust_class.and_then(|r| r.vanilla_methods.get(java_method));
+                (
+                    ImplementationStatus::Implemented,
+                    Some(MatchSource::Explicit),
+                )
+            } else if let Some(rust_method) = rust_equiv_opt {
+                // Steel has this method in its trait
+                if rust_methods.iter().any(|m| m == rust_method) {
+                    rust_fn = Some(rust_method);
+                    (
+                        ImplementationStatus::Implemented,
+                        Some(MatchSource::Inferred),
+                    )
+                } else {
+                    (ImplementationStatus::NotImplemented, None)
+                }
+            } else {
+                // Steel doesn't have this method yet
+                (ImplementationStatus::NotImplemented, None)
+            };
+
+            // Verified against a different vanilla body than the current one
+            let fingerprint = java_class.method_fingerprints.get(java_method).cloned();
+            let verified = config.verified.get(&java_class.class_name, java_method);
+            let status = match (status, verified, &fingerprint) {
+                (ImplementationStatus::Implemented, Some(verified), Some(current))
+                    if verified != current =>
+                {
+                    ImplementationStatus::Stale
+                }
+                (status, _, _) => status,
+            };
+
+            method_tracking.push(MethodTracking {
+                method_name: java_method.clone(),
+                status,
+                metrics: java_class.method_metrics.get(java_method).copied(),
+                ignore_reason,
+                matched_by,
+                fingerprint,
+                rust_span: rust_fn
+                    .and_then(|f| rust_class?.method_spans.get(f)?.first())
+                    .cloned(),
+                last_touched_by: None,
+            });
+        }
+    }
+    method_tracking
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SourceSpan;
+
+    fn steel_type(name: &str, claims: &[&str]) -> ClassMethods {
+        ClassMethods {
+            class_name: name.to_string(),
+            vanilla_classes: claims.iter().map(|c| c.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn duplicate_claims_are_reported_and_resolved_by_name() {
+        for rust_classes in [
+            vec![
+                steel_type("Zed", &["ChestBlock"]),
+                steel_type("Alpha", &["ChestBlock"]),
+            ],
+            vec![
+                steel_type("Alpha", &["ChestBlock"]),
+                steel_type("Zed", &["ChestBlock"]),
+            ],
+        ] {
+            let index = RustIndex::new(&rust_classes, &[]);
+            assert_eq!(index.explicit["ChestBlock"].steel_type, "Alpha");
+            assert_eq!(index.claim_conflicts.len(), 1);
+            assert_eq!(index.claim_conflicts[0].steel_types, ["Alpha", "Zed"]);
+        }
+    }
+
+    #[test]
+    fn struct_markers_claim_classes() {
+        let marked = |name: &str, claims: &[&str]| RustStruct {
+            name: name.to_string(),
+            category: "block".to_string(),
+            span: SourceSpan {
+                path: format!("behavior/blocks/{}.rs", name.to_lowercase()),
+                start_line: 1,
+                end_line: 1,
+                start_byte: 0,
+                end_byte: 0,
+            },
+            vanilla_classes: claims.iter().map(|c| c.to_string()).collect(),
+        };
+        let rust_classes = vec![steel_type("BarrelBlock", &[])];
+        let rust_structs = vec![
+            marked("BarrelBlock", &["ChestBlock"]),
+            marked("MarkerBlock", &["TrappedChestBlock"]),
+        ];
+        let index = RustIndex::new(&rust_classes, &rust_structs);
+
+        let chest = index.explicit["ChestBlock"];
+        assert_eq!(chest.steel_type, "BarrelBlock");
+        assert!(chest.class.is_some());
+        // Claimed even without methods to judge against
+        let trapped = index.explicit["TrappedChestBlock"];
+        assert_eq!(trapped.steel_type, "MarkerBlock");
+        assert!(trapped.class.is_none());
+    }
+}
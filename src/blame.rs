@@ -0,0 +1,265 @@
+//! Who implemented what: git blame over the Steel functions behind Implemented methods.
+//!
+//! Blame credits the last commit to touch a line, so moves, copies and whitespace changes are
+//! ignored to keep refactors from taking credit for the original implementation.
+
+use crate::provider;
+use crate::{AnalysisResult, Attribution};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Uncommitted lines are blamed on this id
+const NOT_COMMITTED: &str = "0000000000000000000000000000000000000000";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContributorSummary {
+    pub author: String,
+    pub email: String,
+    /// Distinct Rust functions, however many vanilla methods each stands in for
+    pub methods: usize,
+    pub classes: usize,
+    pub commits: usize,
+}
+
+/// Set `last_touched_by` on every Implemented method with a Rust source span, blaming the
+/// repository holding `steel_core` at `revision` (the working tree when `None`). A method is
+/// attributed to the commit owning most lines of its function.
+pub fn attribute(result: &mut AnalysisResult, steel_core: &str, revision: Option<&str>) {
+    let mut files: HashMap<String, Vec<Option<Attribution>>> = HashMap::new();
+
+    for method in result.classes.iter_mut().flat_map(|c| c.methods.iter_mut()) {
+        if !method.status.is_implemented() {
+            continue;
+        }
+        let Some(span) = &method.rust_span else {
+            continue;
+        };
+        let lines = files
+            .entry(span.path.clone())
+            .or_insert_with(|| blame_file(steel_core, revision, &span.path));
+        let start = span.start_line.saturating_sub(1).min(lines.len());
+        let end = span.end_line.min(lines.len());
+
+        method.last_touched_by = most_lines(lines[start..end].iter().flatten()).cloned();
+    }
+}
+
+/// The commit owning most of `lines`; ties go to the earlier commit, then the lower hash
+fn most_lines<'a>(lines: impl Iterator<Item = &'a Attribution>) -> Option<&'a Attribution> {
+    let mut counts: HashMap<&str, (usize, &Attribution)> = HashMap::new();
+    for attribution in lines {
+        counts
+            .entry(&attribution.commit)
+            .or_insert((0, attribution))
+            .0 += 1;
+    }
+    counts
+        .into_values()
+        .max_by(|a, b| {
+            a.0.cmp(&b.0)
+                .then(b.1.author_time.cmp(&a.1.author_time))
+                .then(b.1.commit.cmp(&a.1.commit))
+        })
+        .map(|(_, attribution)| attribution)
+}
+
+/// Per-line attribution of a file, `None` for uncommitted lines
+fn blame_file(steel_core: &str, revision: Option<&str>, path: &str) -> Vec<Option<Attribution>> {
+    let mut args = vec!["blame", "--line-porcelain", "-w", "-M", "-C"];
+    args.extend(revision);
+    args.extend(["--", path]);
+    provider::git(Path::new(steel_core), &args)
+        .map(|output| parse_porcelain(&output))
+        .unwrap_or_default()
+}
+
+/// Per-line attribution from `git blame --line-porcelain` output
+fn parse_porcelain(output: &str) -> Vec<Option<Attribution>> {
+    let mut lines = Vec::new();
+    let mut current: Option<Attribution> = None;
+    for line in output.lines() {
+        if let Some(_content) = line.strip_prefix('\t') {
+            lines.push(current.take().filter(|a| a.commit != NOT_COMMITTED));
+        } else if let Some(author) = line.strip_prefix("author ") {
+            if let Some(a) = current.as_mut() {
+                a.author = author.to_string();
+            }
+        } else if let Some(email) = line.strip_prefix("author-mail ") {
+            if let Some(a) = current.as_mut() {
+                a.email = email.trim_matches(['<', '>']).to_string();
+            }
+        } else if let Some(time) = line.strip_prefix("author-time ") {
+            if let Some(a) = current.as_mut() {
+                a.author_time = time.parse().unwrap_or_default();
+            }
+        } else if current.is_none()
+            && let Some(commit) = line.split(' ').next().filter(|c| c.len() == 40)
+        {
+            current = Some(Attribution {
+                author: String::new(),
+                email: String::new(),
+                commit: commit.to_string(),
+                author_time: 0,
+            });
+        }
+    }
+    lines
+}
+
+/// Attributed Rust functions, classes and commits per author, most functions first. Classes
+/// judged against a generic fallback are skipped, and a function standing in for several vanilla
+/// methods counts once.
+pub fn contributor_summary(result: &AnalysisResult) -> Vec<ContributorSummary> {
+    type Counts<'a> = (
+        HashSet<(&'a str, usize)>,
+        HashSet<&'a str>,
+        HashSet<&'a str>,
+    );
+    let mut by_author: HashMap<(&str, &str), Counts> = HashMap::new();
+    for class in result
+        .classes
+        .iter()
+        .filter(|c| c.generic_fallback.is_none())
+    {
+        for method in &class.methods {
+            let (Some(attribution), Some(span)) = (&method.last_touched_by, &method.rust_span)
+            else {
+                continue;
+            };
+            let counts = by_author
+                .entry((&attribution.author, &attribution.email))
+                .or_default();
+            counts.0.insert((&span.path, span.start_line));
+            counts.1.insert(&class.class_name);
+            counts.2.insert(&attribution.commit);
+        }
+    }
+
+    let mut summaries: Vec<ContributorSummary> = by_author
+        .into_iter()
+        .map(
+            |((author, email), (methods, classes, commits))| ContributorSummary {
+                author: author.to_string(),
+                email: email.to_string(),
+                methods: methods.len(),
+                classes: classes.len(),
+                commits: commits.len(),
+            },
+        )
+        .collect();
+    summaries.sort_by(|a, b| b.methods.cmp(&a.methods).then(a.author.cmp(&b.author)));
+    summaries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ClassTracking, ImplementationStatus, MethodTracking, SourceSpan};
+
+    const ALICE: &str = "1111111111111111111111111111111111111111";
+    const BOB: &str = "2222222222222222222222222222222222222222";
+
+    fn attribution(commit: &str, author_time: i64) -> Attribution {
+        Attribution {
+            author: String::new(),
+            email: String::new(),
+            commit: commit.to_string(),
+            author_time,
+        }
+    }
+
+    #[test]
+    fn porcelain_lines() {
+        let output = format!(
+            "{ALICE} 1 1 1\nauthor Alice\nauthor-mail <alice@example.com>\nauthor-time 100\n\
+             summary Barrel\nfilename barrel.rs\n\tfn on_use() {{\n\
+             {NOT_COMMITTED} 2 2 1\nauthor Not Committed Yet\nauthor-time 200\n\tfoo();\n"
+        );
+        let lines = parse_porcelain(&output);
+        assert_eq!(lines.len(), 2);
+        let alice = lines[0].as_ref().unwrap();
+        assert_eq!(
+            (
+                alice.author.as_str(),
+                alice.email.as_str(),
+                alice.author_time
+            ),
+            ("Alice", "alice@example.com", 100)
+        );
+        assert_eq!(alice.commit, ALICE);
+        assert!(lines[1].is_none());
+    }
+
+    #[test]
+    fn ties_are_deterministic() {
+        let lines = [attribution(BOB, 100), attribution(ALICE, 100)];
+        assert_eq!(most_lines(lines.iter()).unwrap().commit, ALICE);
+        assert_eq!(most_lines(lines.iter().rev()).unwrap().commit, ALICE);
+
+        let lines = [attribution(BOB, 50), attribution(ALICE, 100)];
+        assert_eq!(most_lines(lines.iter()).unwrap().commit, BOB);
+
+        let lines = [
+            attribution(BOB, 50),
+            attribution(ALICE, 100),
+            attribution(ALICE, 100),
+        ];
+        assert_eq!(most_lines(lines.iter()).unwrap().commit, ALICE);
+    }
+
+    #[test]
+    fn functions_count_once_and_fallbacks_are_skipped() {
+        let alice = Attribution {
+            author: "Alice".to_string(),
+            email: "alice@example.com".to_string(),
+            commit: ALICE.to_string(),
+            author_time: 100,
+        };
+        let method = |name: &str| MethodTracking {
+            method_name: name.to_string(),
+            status: ImplementationStatus::Implemented,
+            metrics: None,
+            ignore_reason: None,
+            matched_by: None,
+            fingerprint: None,
+            rust_span: Some(SourceSpan {
+                path: "behavior/blocks/barrel.rs".to_string(),
+                start_line: 10,
+                end_line: 20,
+                start_byte: 0,
+                end_byte: 0,
+            }),
+            last_touched_by: Some(alice.clone()),
+        };
+        let class = |name: &str, generic_fallback: Option<&str>| ClassTracking {
+            class_name: name.to_string(),
+            class_type: "block".to_string(),
+            percentage_implemented: 100.0,
+            weighted_percentage_implemented: 100.0,
+            methods: vec![method("onUse"), method("onUseWithItem")],
+            registry_usage: 0,
+            generic_fallback: generic_fallback.map(str::to_string),
+            rust_class: None,
+            class_matched_by: None,
+            name_match: None,
+            secondary_behaviors: Vec::new(),
+        };
+        let result = AnalysisResult {
+            classes: vec![
+                class("BarrelBlock", None),
+                class("ChestBlock", None),
+                class("StoneBlock", Some("Block")),
+            ],
+            match_reviews: Vec::new(),
+            claim_conflicts: Vec::new(),
+        };
+
+        let summary = contributor_summary(&result);
+        assert_eq!(summary.len(), 1);
+        assert_eq!(
+            (summary[0].methods, summary[0].classes, summary[0].commits),
+            (1, 2, 1)
+        );
+    }
+}
@@ -0,0 +1,363 @@
+//! Release notes from the difference between two analysis snapshots.
+
+use crate::analysis;
+use crate::config::Config;
+use crate::coverage::CATEGORIES;
+use crate::history;
+use crate::{AnalysisResult, ClassTracking, Error, ImplementationStatus};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+/// How one vanilla class changed between two snapshots
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassChange {
+    pub class_name: String,
+    pub newly_implemented: Vec<String>,
+    pub regressed: Vec<String>,
+    pub percentage_before: f32,
+    pub percentage_after: f32,
+    /// No longer tracked at all; `regressed` holds what it had implemented
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub removed: bool,
+}
+
+impl ClassChange {
+    /// Went from no coverage to some
+    pub fn is_started(&self) -> bool {
+        self.percentage_before == 0.0 && self.percentage_after > 0.0
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryChanges {
+    pub category: String,
+    pub classes: Vec<ClassChange>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Changelog {
+    pub categories: Vec<CategoryChanges>,
+}
+
+/// Changes from `before` to `after`. Classes only tracked in `after` count from zero; classes
+/// no longer tracked regress everything they had implemented. Fallback classes are left out on
+/// both sides.
+pub fn diff(before: &AnalysisResult, after: &AnalysisResult) -> Changelog {
+    let before_classes: HashMap<&str, &ClassTracking> = history::tracked_classes(before)
+        .map(|c| (c.class_name.as_str(), c))
+        .collect();
+
+    let mut by_category: HashMap<&str, Vec<ClassChange>> = HashMap::new();
+    for class in history::tracked_classes(after) {
+        let previous = before_classes.get(class.class_name.as_str());
+        let was_implemented = |method: &str| {
+            previous.is_some_and(|p| {
+                p.methods
+                    .iter()
+                    .any(|m| m.method_name == method && m.status.is_implemented())
+            })
+        };
+
+        let mut newly_implemented = Vec::new();
+        let mut regressed = Vec::new();
+        for method in &class.methods {
+            let implemented_before = was_implemented(&method.method_name);
+            match method.status {
+                ImplementationStatus::Implemented | ImplementationStatus::Stale
+                    if !implemented_before =>
+                {
+                    newly_implemented.push(method.method_name.clone())
+                }
+                ImplementationStatus::NotImplemented if implemented_before => {
+                    regressed.push(method.method_name.clone())
+                }
+                _ => {}
+            }
+        }
+        if newly_implemented.is_empty() && regressed.is_empty() {
+            continue;
+        }
+        newly_implemented.sort();
+        regressed.sort();
+
+        by_category
+            .entry(class.class_type.as_str())
+            .or_default()
+            .push(ClassChange {
+                class_name: class.class_name.clone(),
+                newly_implemented,
+                regressed,
+                percentage_before: previous.map_or(0.0, |p| p.percentage_implemented),
+                percentage_after: class.percentage_implemented,
+                removed: false,
+            });
+    }
+
+    let after_classes: HashSet<&str> = history::tracked_classes(after)
+        .map(|c| c.class_name.as_str())
+        .collect();
+    for class in history::tracked_classes(before) {
+        if after_classes.contains(class.class_name.as_str()) {
+            continue;
+        }
+        let mut regressed: Vec<String> = class
+            .methods
+            .iter()
+            .filter(|m| m.status.is_implemented())
+            .map(|m| m.method_name.clone())
+            .collect();
+        if regressed.is_empty() {
+            continue;
+        }
+        regressed.sort();
+        by_category
+            .entry(class.class_type.as_str())
+            .or_default()
+            .push(ClassChange {
+                class_name: class.class_name.clone(),
+                newly_implemented: Vec::new(),
+                regressed,
+                percentage_before: class.percentage_implemented,
+                percentage_after: 0.0,
+                removed: true,
+            });
+    }
+
+    let mut extra: Vec<&str> = by_category
+        .keys()
+        .copied()
+        .filter(|c| !CATEGORIES.contains(c))
+        .collect();
+    extra.sort();
+
+    let mut categories = Vec::new();
+    for category in CATEGORIES.iter().copied().chain(extra) {
+        let Some(mut classes) = by_category.remove(category) else {
+            continue;
+        };
+        classes.sort_by(|a, b| a.class_name.cmp(&b.class_name));
+        categories.push(CategoryChanges {
+            category: category.to_string(),
+            classes,
+        });
+    }
+    Changelog { categories }
+}
+
+/// Changes between the two ends of a git range `A..B` of the repository holding
+/// `config.steel_core`; `B` defaults to `HEAD`
+pub fn from_range(config: &Config, range: &str) -> Result<Changelog, Error> {
+    let (from, to) = range_ends(range)?;
+
+    let mut sources = analysis::load_yarn_sources(config)?;
+    let before = history::analyze_revision(config, &mut sources, from)?;
+    let after = history::analyze_revision(config, &mut sources, to)?;
+    Ok(diff(&before, &after))
+}
+
+/// `(A, B)` of `A..B`, `A..` or a lone `A`, with `B` defaulting to `HEAD`
+fn range_ends(range: &str) -> Result<(&str, &str), Error> {
+    let invalid = |why: &str| Err(Error::load(range, why.into()));
+    if range.contains("...") {
+        return invalid("symmetric ranges are not supported, use A..B");
+    }
+    let (from, to) = range.split_once("..").unwrap_or((range, ""));
+    if from.is_empty() {
+        return invalid("the range needs a start revision");
+    }
+    if to.contains("..") {
+        return invalid("expected a single A..B range");
+    }
+    Ok((from, if to.is_empty() { "HEAD" } else { to }))
+}
+
+impl Changelog {
+    pub fn is_empty(&self) -> bool {
+        self.categories.is_empty()
+    }
+
+    /// Markdown release notes, one section per category
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("# Vanilla parity changes\n");
+        if self.is_empty() {
+            out.push_str("\nNo changes in tracked vanilla behavior.\n");
+        }
+
+        for category in &self.categories {
+            let _ = writeln!(out, "\n## {}", category_title(&category.category));
+            let (started, rest): (Vec<_>, Vec<_>) =
+                category.classes.iter().partition(|c| c.is_started());
+
+            if rest.len() < category.classes.len()
+                || rest.iter().any(|c| !c.newly_implemented.is_empty())
+            {
+                out.push('\n');
+            }
+            for class in started {
+                let extent = if class.percentage_after >= 100.0 {
+                    "fully"
+                } else {
+                    "partially"
+                };
+                let _ = writeln!(
+                    out,
+                    "- {} is now {extent} implemented: {} ({})",
+                    class.class_name,
+                    class.newly_implemented.join(", "),
+                    percentage_change(class)
+                );
+            }
+            for class in rest.iter().filter(|c| !c.newly_implemented.is_empty()) {
+                let _ = writeln!(
+                    out,
+                    "- {} now implements {} ({})",
+                    class.class_name,
+                    class.newly_implemented.join(", "),
+                    percentage_change(class)
+                );
+            }
+            let regressions: Vec<&&ClassChange> =
+                rest.iter().filter(|c| !c.regressed.is_empty()).collect();
+            if !regressions.is_empty() {
+                let _ = writeln!(out, "\n### Regressions\n");
+                for class in regressions {
+                    let what = if class.removed {
+                        "is no longer tracked, losing"
+                    } else {
+                        "no longer implements"
+                    };
+                    let _ = writeln!(
+                        out,
+                        "- {} {what} {} ({})",
+                        class.class_name,
+                        class.regressed.join(", "),
+                        percentage_change(class)
+                    );
+                }
+            }
+        }
+        out
+    }
+}
+
+fn percentage_change(class: &ClassChange) -> String {
+    format!(
+        "{:.0}% -> {:.0}%",
+        class.percentage_before, class.percentage_after
+    )
+}
+
+/// Section heading for a category
+fn category_title(category: &str) -> String {
+    let title = match category {
+        "block" => "Blocks",
+        "block_entity" => "Block Entities",
+        "item" => "Items",
+        "entity" => "Entities",
+        "mob" => "Mobs",
+        "projectile" => "Projectiles",
+        "vehicle" => "Vehicles",
+        "ai_goal" => "AI Goals",
+        "ai_brain_task" => "Brain Tasks",
+        "ai_brain_sensor" => "Brain Sensors",
+        "ai_control" => "AI Controls",
+        "ai_pathing" => "Pathfinding",
+        "other" => "Other",
+        other => return other.replace('_', " "),
+    };
+    title.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MethodTracking;
+
+    fn class(name: &str, implemented: &[&str], missing: &[&str]) -> ClassTracking {
+        let method = |name: &&str, status: ImplementationStatus| MethodTracking {
+            method_name: name.to_string(),
+            status,
+            metrics: None,
+            ignore_reason: None,
+            matched_by: None,
+            fingerprint: None,
+            rust_span: None,
+            last_touched_by: None,
+        };
+        let methods: Vec<MethodTracking> = implemented
+            .iter()
+            .map(|m| method(m, ImplementationStatus::Implemented))
+            .chain(
+                missing
+                    .iter()
+                    .map(|m| method(m, ImplementationStatus::NotImplemented)),
+            )
+            .collect();
+        ClassTracking {
+            class_name: name.to_string(),
+            class_type: "block".to_string(),
+            percentage_implemented: crate::Coverage::raw(&methods).percentage(),
+            weighted_percentage_implemented: 0.0,
+            methods,
+            registry_usage: 0,
+            generic_fallback: None,
+            rust_class: None,
+            class_matched_by: None,
+            name_match: None,
+            secondary_behaviors: Vec::new(),
+        }
+    }
+
+    fn snapshot(classes: Vec<ClassTracking>) -> AnalysisResult {
+        AnalysisResult {
+            classes,
+            match_reviews: Vec::new(),
+            claim_conflicts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn implemented_and_regressed_methods() {
+        let before = snapshot(vec![class("BarrelBlock", &["onUse"], &["tick"])]);
+        let after = snapshot(vec![class("BarrelBlock", &["tick"], &["onUse"])]);
+        let changelog = diff(&before, &after);
+        let change = &changelog.categories[0].classes[0];
+        assert_eq!(change.newly_implemented, ["tick"]);
+        assert_eq!(change.regressed, ["onUse"]);
+        assert!(!change.removed);
+    }
+
+    #[test]
+    fn vanished_class_is_a_regression() {
+        let before = snapshot(vec![class("ChestBlock", &["onUse"], &["tick"])]);
+        let changelog = diff(&before, &snapshot(Vec::new()));
+        let change = &changelog.categories[0].classes[0];
+        assert!(change.removed);
+        assert_eq!(change.regressed, ["onUse"]);
+        assert!(
+            changelog
+                .to_markdown()
+                .contains("ChestBlock is no longer tracked")
+        );
+    }
+
+    #[test]
+    fn fallback_classes_are_left_out() {
+        let mut fallback = class("StoneBlock", &["onUse"], &[]);
+        fallback.generic_fallback = Some("Block".to_string());
+        let before = snapshot(vec![fallback.clone()]);
+        assert!(diff(&before, &snapshot(Vec::new())).categories.is_empty());
+        assert!(diff(&snapshot(Vec::new()), &before).categories.is_empty());
+    }
+
+    #[test]
+    fn range_ends_are_validated() {
+        assert_eq!(range_ends("v1..v2").unwrap(), ("v1", "v2"));
+        assert_eq!(range_ends("v1..").unwrap(), ("v1", "HEAD"));
+        assert_eq!(range_ends("v1").unwrap(), ("v1", "HEAD"));
+        assert!(range_ends("v1...v2").is_err());
+        assert!(range_ends("..v2").is_err());
+        assert!(range_ends("").is_err());
+    }
+}
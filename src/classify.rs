@@ -0,0 +1,66 @@
+//! Category of a vanilla class from the package its source file declares.
+
+use crate::utils;
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PackageRule {
+    /// Package prefix, matched on `.` boundaries
+    pub package: String,
+    pub category: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PackageRules {
+    pub rules: Vec<PackageRule>,
+    /// Category of classes no rule matches
+    pub default: String,
+}
+
+/// The rule table shipped in `config/packages.json`
+impl Default for PackageRules {
+    fn default() -> Self {
+        serde_json::from_str(include_str!("../config/packages.json"))
+            .expect("config/packages.json is valid")
+    }
+}
+
+impl PackageRules {
+    /// Load the rule table, falling back to the built-in rules when the file does not exist
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        utils::load_json_or_default(path)
+    }
+
+    /// Category of the longest rule prefix matching `package`
+    pub fn classify(&self, package: &str) -> &str {
+        self.rules
+            .iter()
+            .filter(|rule| {
+                package
+                    .strip_prefix(rule.package.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+            })
+            .max_by_key(|rule| rule.package.len())
+            .map(|rule| rule.category.as_str())
+            .unwrap_or(&self.default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn longest_prefix_on_package_boundaries() {
+        let rules = PackageRules::default();
+        assert_eq!(rules.classify("net.minecraft.block"), "block");
+        assert_eq!(rules.classify("net.minecraft.block.entity"), "block_entity");
+        assert_eq!(
+            rules.classify("net.minecraft.entity.ai.brain.task"),
+            "ai_brain_task"
+        );
+        assert_eq!(rules.classify("net.minecraft.entity.mob"), "mob");
+        assert_eq!(rules.classify("net.minecraft.blockade"), "other");
+        assert_eq!(rules.classify(""), "other");
+    }
+}
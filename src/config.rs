@@ -0,0 +1,144 @@
+//! Input locations and rule tables for an analysis run.
+
+use crate::Error;
+use crate::classify::PackageRules;
+use crate::extractors::JavaExtractorOptions;
+use crate::fallback::FallbackConfig;
+use crate::ignore::IgnoreList;
+use crate::mappings::MethodTables;
+use crate::matching::MatcherConfig;
+use crate::profile::Profile;
+use crate::provider::{self, DirectoryProvider, GitProvider, SourceProvider};
+use crate::verified::VerifiedLock;
+use std::fs;
+use std::path::Path;
+
+/// Steel's registry id -> vanilla class table, relative to `steel_core`
+const CLASSES_JSON_PATH: &str = "build/classes.json";
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Decompiled yarn sources (`namedSrc`), as a directory or a sources jar
+    pub yarn_src: String,
+    /// The `steel-core` crate of SteelMC
+    pub steel_core: String,
+    /// Read `steel_core` at this git revision instead of from the working tree
+    pub steel_revision: Option<String>,
+    /// Folders under `net/minecraft` parsed for vanilla classes
+    pub java_folders: Vec<String>,
+    /// Folders under `steel-core/src` parsed for Steel types, with the category they hold
+    pub rust_folders: Vec<(String, String)>,
+    pub java_options: JavaExtractorOptions,
+    pub method_tables: MethodTables,
+    pub package_rules: PackageRules,
+    pub fallback: FallbackConfig,
+    pub ignore: IgnoreList,
+    pub matcher: MatcherConfig,
+    /// Vanilla fingerprints implementations were verified against, and where they are stored
+    pub verified: VerifiedLock,
+    pub verified_path: String,
+    /// Where reports are written
+    pub output_dir: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            yarn_src: "sources/yarn/build/namedSrc".to_string(),
+            steel_core: "sources/SteelMC/steel-core".to_string(),
+            steel_revision: None,
+            java_folders: ["block", "item", "entity"].map(String::from).to_vec(),
+            rust_folders: [
+                ("behavior/blocks", "block"),
+                ("behavior/items", "item"),
+                ("entity", "entity"),
+                ("ai", "ai"),
+                ("block_entity", "block_entity"),
+            ]
+            .map(|(folder, category)| (folder.to_string(), category.to_string()))
+            .to_vec(),
+            java_options: JavaExtractorOptions::default(),
+            method_tables: MethodTables::default(),
+            package_rules: PackageRules::default(),
+            fallback: FallbackConfig::default(),
+            ignore: IgnoreList::default(),
+            matcher: MatcherConfig::default(),
+            verified: VerifiedLock::default(),
+            verified_path: "config/verified.lock.json".to_string(),
+            output_dir: "outputs".to_string(),
+        }
+    }
+}
+
+impl Config {
+    /// Default paths with the rule tables found in `config_dir`; missing files keep their
+    /// built-in defaults
+    pub fn from_config_dir(config_dir: &str) -> Result<Self, Error> {
+        let path = |file: &str| format!("{config_dir}/{file}");
+        let packages = path("packages.json");
+        let fallbacks = path("generic_fallbacks.json");
+        let ignore = path("ignore.json");
+        let matching = path("matching.json");
+        let verified = path("verified.lock.json");
+
+        Ok(Self {
+            package_rules: PackageRules::load(&packages).map_err(|e| Error::load(&packages, e))?,
+            fallback: FallbackConfig::load(&fallbacks).map_err(|e| Error::load(&fallbacks, e))?,
+            ignore: IgnoreList::load(&ignore).map_err(|e| Error::load(&ignore, e))?,
+            matcher: MatcherConfig::load(&matching).map_err(|e| Error::load(&matching, e))?,
+            verified: VerifiedLock::load(&verified).map_err(|e| Error::load(&verified, e))?,
+            verified_path: verified,
+            ..Self::default()
+        })
+    }
+
+    /// `from_config_dir` with the named profile and the profiles it extends applied. Outputs
+    /// go to `outputs/<profile>` so versions can be compared side by side.
+    ///
+    /// Each profile keeps its own `profiles/<name>.verified.lock.json`; until it has one, the
+    /// lock of the nearest profile it extends (or the default lock) is used, with renames
+    /// applied.
+    pub fn from_profile(config_dir: &str, name: &str) -> Result<Self, Error> {
+        let mut config = Self::from_config_dir(config_dir)?;
+        for (profile_name, profile) in Profile::resolve(config_dir, name)? {
+            profile.apply(&mut config);
+            let lock = Profile::verified_path(config_dir, &profile_name);
+            if Path::new(&lock).exists() {
+                config.verified = VerifiedLock::load(&lock).map_err(|e| Error::load(&lock, e))?;
+            }
+        }
+        config.verified_path = Profile::verified_path(config_dir, name);
+        config.output_dir = format!("{}/{name}", config.output_dir);
+        Ok(config)
+    }
+
+    pub fn yarn_provider(&self) -> Result<Box<dyn SourceProvider>, Error> {
+        provider::open(&self.yarn_src)
+    }
+
+    pub fn steel_provider(&self) -> Result<Box<dyn SourceProvider>, Error> {
+        match &self.steel_revision {
+            Some(revision) => Ok(Box::new(GitProvider::new(&self.steel_core, revision)?)),
+            None => Ok(Box::new(DirectoryProvider::new(&self.steel_core))),
+        }
+    }
+
+    pub fn classes_json_path(&self) -> String {
+        format!("{}/{CLASSES_JSON_PATH}", self.steel_core)
+    }
+
+    /// Where classes.json was read from and its content. It is a build output, so at a git
+    /// revision that lacks it the working tree copy is used, as for the generated registrations.
+    pub fn read_classes_json(&self) -> Result<Option<(String, String)>, Error> {
+        if let Some(revision) = &self.steel_revision {
+            let steel = GitProvider::new(&self.steel_core, revision)?;
+            if let Some(content) = steel.read(CLASSES_JSON_PATH) {
+                return Ok(Some((format!("{revision}:{CLASSES_JSON_PATH}"), content)));
+            }
+        }
+        let path = self.classes_json_path();
+        Ok(fs::read_to_string(&path)
+            .ok()
+            .map(|content| (path, content)))
+    }
+}
@@ -0,0 +1,151 @@
+//! Raw and weighted coverage over tracked methods.
+
+use crate::{AnalysisResult, ClassTracking, ImplementationStatus, MethodTracking};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Coverage {
+    pub implemented: f32,
+    pub total: f32,
+}
+
+impl Coverage {
+    /// Every tracked method counts once.
+    pub fn raw(methods: &[MethodTracking]) -> Self {
+        Self::by(methods, |_| 1.0)
+    }
+
+    /// Every tracked method counts by the complexity of its vanilla body.
+    pub fn weighted(methods: &[MethodTracking]) -> Self {
+        Self::by(methods, MethodTracking::weight)
+    }
+
+    /// A class counts once per registry entry using it, scaled by its raw coverage.
+    /// Fully ignored classes count towards neither side.
+    pub fn usage_weighted(class: &ClassTracking) -> Self {
+        if Self::raw(&class.methods).total == 0.0 {
+            return Coverage::default();
+        }
+        let usage = class.registry_usage as f32;
+        Coverage {
+            implemented: usage * class.percentage_implemented / 100.0,
+            total: usage,
+        }
+    }
+
+    /// Ignored methods count towards neither side.
+    fn by(methods: &[MethodTracking], weight: impl Fn(&MethodTracking) -> f32) -> Self {
+        let mut coverage = Coverage::default();
+        for method in methods {
+            if method.status == ImplementationStatus::Ignored {
+                continue;
+            }
+            let w = weight(method);
+            coverage.total += w;
+            if method.status.is_implemented() {
+                coverage.implemented += w;
+            }
+        }
+        coverage
+    }
+
+    pub fn merge(&mut self, other: Coverage) {
+        self.implemented += other.implemented;
+        self.total += other.total;
+    }
+
+    pub fn percentage(&self) -> f32 {
+        if self.total > 0.0 {
+            (self.implemented / self.total) * 100.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Categories in reporting order; categories from custom package rules follow alphabetically
+pub const CATEGORIES: [&str; 13] = [
+    "block",
+    "block_entity",
+    "item",
+    "entity",
+    "mob",
+    "projectile",
+    "vehicle",
+    "ai_goal",
+    "ai_brain_task",
+    "ai_brain_sensor",
+    "ai_control",
+    "ai_pathing",
+    "other",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategorySummary {
+    pub category: String,
+    /// Classes counted towards the percentages
+    pub classes: usize,
+    /// Classes backed by a generic fallback, excluded from the percentages
+    pub fallback_classes: usize,
+    pub ignored_methods: usize,
+    /// Implemented against an older vanilla body than the current one
+    #[serde(default)]
+    pub stale_methods: usize,
+    pub percentage_implemented: f32,
+    pub weighted_percentage_implemented: f32,
+    /// Only for categories with registry entries
+    pub usage_weighted_percentage: Option<f32>,
+}
+
+pub fn summarize(result: &AnalysisResult) -> Vec<CategorySummary> {
+    let mut categories: Vec<&str> = CATEGORIES.to_vec();
+    let mut extra: Vec<&str> = result
+        .classes
+        .iter()
+        .map(|c| c.class_type.as_str())
+        .filter(|c| !CATEGORIES.contains(c))
+        .collect();
+    extra.sort();
+    extra.dedup();
+    categories.extend(extra);
+
+    let mut summaries = Vec::new();
+    for category in categories {
+        let (fallback_classes, classes): (Vec<_>, Vec<_>) = result
+            .classes
+            .iter()
+            .filter(|c| c.class_type == category)
+            .partition(|c| c.generic_fallback.is_some());
+        if classes.is_empty() && fallback_classes.is_empty() {
+            continue;
+        }
+
+        let mut raw = Coverage::default();
+        let mut weighted = Coverage::default();
+        let mut usage = Coverage::default();
+        for class in &classes {
+            raw.merge(Coverage::raw(&class.methods));
+            weighted.merge(Coverage::weighted(&class.methods));
+            usage.merge(Coverage::usage_weighted(class));
+        }
+        let count = |status: ImplementationStatus| {
+            classes
+                .iter()
+                .flat_map(|c| &c.methods)
+                .filter(|m| m.status == status)
+                .count()
+        };
+
+        summaries.push(CategorySummary {
+            category: category.to_string(),
+            classes: classes.len(),
+            fallback_classes: fallback_classes.len(),
+            ignored_methods: count(ImplementationStatus::Ignored),
+            stale_methods: count(ImplementationStatus::Stale),
+            percentage_implemented: raw.percentage(),
+            weighted_percentage_implemented: weighted.percentage(),
+            usage_weighted_percentage: (usage.total > 0.0).then(|| usage.percentage()),
+        });
+    }
+    summaries
+}
@@ -0,0 +1,39 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum Error {
+    /// A config or input file could not be read or parsed
+    Load {
+        path: String,
+        source: Box<dyn std::error::Error>,
+    },
+    /// A required source tree is missing or empty
+    MissingSource(String),
+}
+
+impl Error {
+    pub fn load(path: &str, source: Box<dyn std::error::Error>) -> Self {
+        Error::Load {
+            path: path.to_string(),
+            source,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Load { path, source } => write!(f, "failed to load {path}: {source}"),
+            Error::MissingSource(what) => write!(f, "missing source: {what}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Load { source, .. } => Some(source.as_ref()),
+            Error::MissingSource(_) => None,
+        }
+    }
+}
@@ -1,8 +1,34 @@
-use crate::{ClassInfo, ClassMethods};
-use std::collections::HashMap;
-use tree_sitter::{Parser, StreamingIterator};
+use super::span_of;
+use crate::{ClassInfo, ClassMethods, MethodMetrics};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tree_sitter::{Node, Parser, StreamingIterator};
+
+#[derive(Debug, Clone, Default)]
+pub struct JavaExtractorOptions {
+    /// Keep `@Environment(EnvType.CLIENT)` classes and methods, tagged as client-only,
+    /// instead of dropping them
+    pub include_client_only: bool,
+}
 
 pub fn parse_java_files_unfiltered(path: &str) -> Vec<ClassMethods> {
+    parse_java_files_with_options(path, &JavaExtractorOptions::default())
+}
+
+pub fn parse_java_files_with_options(
+    path: &str,
+    options: &JavaExtractorOptions,
+) -> Vec<ClassMethods> {
+    parse_java_sources(crate::utils::files_with_extension(path, "java"), options)
+}
+
+/// Parse Java sources given as `(path, contents)` pairs, without touching the filesystem
+pub fn parse_java_sources<I, P, S>(sources: I, options: &JavaExtractorOptions) -> Vec<ClassMethods>
+where
+    I: IntoIterator<Item = (P, S)>,
+    P: AsRef<Path>,
+    S: AsRef<str>,
+{
     let mut parser = Parser::new();
 
     let language = tree_sitter_java::LANGUAGE;
@@ -17,22 +43,32 @@ pub fn parse_java_files_unfiltered(path: &str) -> Vec<ClassMethods> {
          (super_interfaces (type_list (type_identifier) @implements))*
          body: (class_body
            (method_declaration
-             name: (identifier) @method_name)))
+             name: (identifier) @method_name) @method)) @class
     "#;
 
     let query = tree_sitter::Query::new(&language.into(), query_str).unwrap();
     let mut cursor = tree_sitter::QueryCursor::new();
     let mut class_info_map = HashMap::<String, ClassInfo>::new();
 
-    crate::utils::walk_files(path, "java", |_path, content| {
-        let tree = parser.parse(&content, None).unwrap();
+    for (path, content) in sources {
+        let path = path.as_ref().to_string_lossy().replace('\\', "/");
+        let content = content.as_ref();
+        let tree = parser.parse(content, None).unwrap();
+        let package = declared_package(tree.root_node(), content.as_bytes());
         let mut matches = cursor.matches(&query, tree.root_node(), content.as_bytes());
+        // The query matches a method once per implemented interface
+        let mut seen_methods = HashSet::new();
 
         while let Some(m) = matches.next() {
             let mut class_name = String::new();
             let mut method_name = String::new();
             let mut extends = None;
             let mut implements = Vec::new();
+            let mut metrics = None;
+            let mut fingerprint = None;
+            let mut span = None;
+            let mut method_client_only = false;
+            let mut class_client_only = false;
 
             for capture in m.captures {
                 let text = capture.node.utf8_text(content.as_bytes()).unwrap();
@@ -41,6 +77,15 @@ pub fn parse_java_files_unfiltered(path: &str) -> Vec<ClassMethods> {
                     1 => extends = Some(text.to_string()),
                     2 => implements.push(text.to_string()),
                     3 => method_name = text.to_string(),
+                    4 => {
+                        method_client_only = is_client_only(capture.node, content.as_bytes());
+                        if seen_methods.insert(capture.node.start_byte()) {
+                            metrics = Some(method_metrics(capture.node));
+                            fingerprint = body_fingerprint(capture.node, content.as_bytes());
+                            span = Some(span_of(&path, capture.node));
+                        }
+                    }
+                    5 => class_client_only = is_client_only(capture.node, content.as_bytes()),
                     _ => {}
                 }
             }
@@ -51,9 +96,15 @@ pub fn parse_java_files_unfiltered(path: &str) -> Vec<ClassMethods> {
                         .entry(class_name.clone())
                         .or_insert_with(|| ClassInfo {
                             name: class_name.clone(),
+                            package: package.clone(),
                             methods: Vec::new(),
                             extends: extends.clone(),
                             implements: implements.clone(),
+                            method_metrics: HashMap::new(),
+                            method_fingerprints: HashMap::new(),
+                            method_spans: HashMap::new(),
+                            client_only: class_client_only,
+                            client_only_methods: HashMap::new(),
                         });
                 if extends.is_some() {
                     class_info.extends = extends;
@@ -66,12 +117,46 @@ pub fn parse_java_files_unfiltered(path: &str) -> Vec<ClassMethods> {
                     }
                 }
 
-                if !method_name.is_empty() && !class_info.methods.contains(&method_name) {
-                    class_info.methods.push(method_name);
+                if !method_name.is_empty() {
+                    class_info
+                        .client_only_methods
+                        .entry(method_name.clone())
+                        .and_modify(|all_client| *all_client &= method_client_only)
+                        .or_insert(method_client_only);
+                    if let Some(metrics) = metrics {
+                        class_info
+                            .method_metrics
+                            .entry(method_name.clone())
+                            .or_default()
+                            .add(metrics);
+                    }
+                    if let Some(span) = span {
+                        class_info
+                            .method_spans
+                            .entry(method_name.clone())
+                            .or_default()
+                            .push(span);
+                    }
+                    // Summed so the result does not depend on overload order
+                    if let Some(fingerprint) = fingerprint {
+                        let sum = class_info
+                            .method_fingerprints
+                            .entry(method_name.clone())
+                            .or_default();
+                        *sum = sum.wrapping_add(fingerprint);
+                    }
+                    if !class_info.methods.contains(&method_name) {
+                        class_info.methods.push(method_name);
+                    }
                 }
             }
         }
-    });
+    }
+
+    // Dropped before the hierarchy is built, so a class with only client-only subclasses stays real
+    if !options.include_client_only {
+        class_info_map.retain(|_, class_info| !class_info.client_only);
+    }
 
     let mut children_map: HashMap<String, Vec<String>> = HashMap::new();
     for class_info in class_info_map.values() {
@@ -91,14 +176,230 @@ pub fn parse_java_files_unfiltered(path: &str) -> Vec<ClassMethods> {
 
     class_info_map
         .into_iter()
-        .map(|(class_name, class_info)| {
+        .map(|(class_name, mut class_info)| {
             let is_real_class = !children_map.contains_key(&class_name);
+            let mut client_only_methods: Vec<String> = class_info
+                .client_only_methods
+                .into_iter()
+                .filter_map(|(method, all_client)| all_client.then_some(method))
+                .collect();
+            if !options.include_client_only {
+                class_info
+                    .methods
+                    .retain(|m| !client_only_methods.contains(m));
+                class_info
+                    .method_metrics
+                    .retain(|m, _| !client_only_methods.contains(m));
+                class_info
+                    .method_fingerprints
+                    .retain(|m, _| !client_only_methods.contains(m));
+                class_info
+                    .method_spans
+                    .retain(|m, _| !client_only_methods.contains(m));
+                client_only_methods.clear();
+            }
+            client_only_methods.sort();
             ClassMethods {
                 class_name,
                 class_type: String::new(),
                 methods: class_info.methods,
                 is_real_class,
+                package: class_info.package,
+                superclass: class_info.extends,
+                method_metrics: class_info.method_metrics,
+                method_fingerprints: class_info
+                    .method_fingerprints
+                    .into_iter()
+                    .map(|(method, hash)| (method, format!("{hash:016x}")))
+                    .collect(),
+                method_spans: class_info.method_spans,
+                client_only: class_info.client_only,
+                client_only_methods,
+                ..Default::default()
             }
         })
         .collect()
 }
+
+/// `net.minecraft.block` from `package net.minecraft.block;`
+fn declared_package(root: Node, source: &[u8]) -> String {
+    let mut cursor = root.walk();
+    root.named_children(&mut cursor)
+        .find(|c| c.kind() == "package_declaration")
+        .and_then(|declaration| declaration.named_child(0))
+        .and_then(|name| name.utf8_text(source).ok())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Whether a class or method declaration carries `@Environment(EnvType.CLIENT)`
+fn is_client_only(declaration: Node, source: &[u8]) -> bool {
+    let mut cursor = declaration.walk();
+    let Some(modifiers) = declaration
+        .children(&mut cursor)
+        .find(|c| c.kind() == "modifiers")
+    else {
+        return false;
+    };
+    let mut cursor = modifiers.walk();
+    modifiers.children(&mut cursor).any(|annotation| {
+        annotation.kind() == "annotation"
+            && annotation
+                .child_by_field_name("name")
+                .and_then(|n| n.utf8_text(source).ok())
+                .is_some_and(|name| name == "Environment" || name.ends_with(".Environment"))
+            && annotation
+                .child_by_field_name("arguments")
+                .and_then(|a| a.utf8_text(source).ok())
+                .is_some_and(|args| args.contains("EnvType.CLIENT"))
+    })
+}
+
+/// FNV-1a of a method body's tokens joined by single spaces, so formatting and comments do not
+/// affect it. `None` for methods without a body.
+fn body_fingerprint(method: Node, source: &[u8]) -> Option<u64> {
+    let body = method.child_by_field_name("body")?;
+    let mut normalized = Vec::new();
+    let mut cursor = body.walk();
+    let mut stack = vec![body];
+    while let Some(node) = stack.pop() {
+        if matches!(node.kind(), "line_comment" | "block_comment") {
+            continue;
+        }
+        if node.child_count() == 0 {
+            if !normalized.is_empty() {
+                normalized.push(b' ');
+            }
+            normalized.extend_from_slice(&source[node.byte_range()]);
+            continue;
+        }
+        // Push in reverse so tokens come out in source order
+        let children: Vec<Node> = node.children(&mut cursor).collect();
+        stack.extend(children.into_iter().rev());
+    }
+    Some(crate::utils::fnv1a(&normalized))
+}
+
+/// Statement count, line span and cyclomatic complexity of a `method_declaration` node.
+fn method_metrics(method: Node) -> MethodMetrics {
+    let mut metrics = MethodMetrics {
+        statements: 0,
+        lines: method.end_position().row - method.start_position().row + 1,
+        cyclomatic: 1,
+    };
+
+    let Some(body) = method.child_by_field_name("body") else {
+        return metrics;
+    };
+
+    let mut cursor = body.walk();
+    let mut stack = vec![body];
+    while let Some(node) = stack.pop() {
+        let kind = node.kind();
+        if kind.ends_with("_statement") || kind == "local_variable_declaration" {
+            metrics.statements += 1;
+        }
+        match kind {
+            "if_statement"
+            | "for_statement"
+            | "enhanced_for_statement"
+            | "while_statement"
+            | "do_statement"
+            | "catch_clause"
+            | "ternary_expression" => metrics.cyclomatic += 1,
+            "switch_label" if node.child(0).is_some_and(|c| c.kind() == "case") => {
+                metrics.cyclomatic += 1
+            }
+            "binary_expression"
+                if node
+                    .child_by_field_name("operator")
+                    .is_some_and(|op| matches!(op.kind(), "&&" | "||")) =>
+            {
+                metrics.cyclomatic += 1
+            }
+            _ => {}
+        }
+        stack.extend(node.named_children(&mut cursor));
+    }
+
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> Vec<ClassMethods> {
+        parse_java_sources([("Foo.java", source)], &JavaExtractorOptions::default())
+    }
+
+    fn class<'a>(classes: &'a [ClassMethods], name: &str) -> &'a ClassMethods {
+        classes.iter().find(|c| c.class_name == name).unwrap()
+    }
+
+    #[test]
+    fn metrics_do_not_depend_on_implemented_interfaces() {
+        let one = parse("class Foo extends Bar implements I1 { void tick() { if (x) { y(); } } }");
+        let three = parse(
+            "class Foo extends Bar implements I1, I2, I3 { void tick() { if (x) { y(); } } }",
+        );
+        let expected = MethodMetrics {
+            statements: 2,
+            lines: 1,
+            cyclomatic: 2,
+        };
+        assert_eq!(class(&one, "Foo").method_metrics["tick"], expected);
+        assert_eq!(class(&three, "Foo").method_metrics["tick"], expected);
+    }
+
+    #[test]
+    fn overload_metrics_are_summed() {
+        let classes =
+            parse("class Foo implements I1, I2 { void a() { x(); } void a(int i) { if (i) {} } }");
+        let metrics = class(&classes, "Foo").method_metrics["a"];
+        assert_eq!(metrics.statements, 2);
+        assert_eq!(metrics.cyclomatic, 3);
+        assert_eq!(metrics.lines, 2);
+    }
+
+    #[test]
+    fn fingerprint_ignores_interfaces_and_formatting() {
+        let fingerprint =
+            |source: &str| class(&parse(source), "Foo").method_fingerprints["tick"].clone();
+        let base = fingerprint("class Foo implements I1 { void tick() { if (x) { y(); } } }");
+        assert_eq!(
+            fingerprint("class Foo implements I1, I2, I3 { void tick() { if (x) { y(); } } }"),
+            base
+        );
+        assert_eq!(
+            fingerprint(
+                "class Foo {\n  void tick() {\n    // note\n    if (x) {\n      y();\n    }\n  }\n}"
+            ),
+            base
+        );
+        assert_ne!(
+            fingerprint("class Foo { void tick() { if (x) { z(); } } }"),
+            base
+        );
+    }
+
+    #[test]
+    fn one_span_per_overload() {
+        let classes =
+            parse("class Foo implements I1, I2, I3 {\n  void tick() {}\n  void tick(int i) {}\n}");
+        let spans = &class(&classes, "Foo").method_spans["tick"];
+        assert_eq!(spans.len(), 2);
+        assert_eq!((spans[0].start_line, spans[1].start_line), (2, 3));
+        assert_eq!(spans[0].path, "Foo.java");
+    }
+
+    #[test]
+    fn client_only_subclasses_do_not_hide_a_server_class() {
+        let classes = parse(
+            "class Base { void tick() {} }\n\
+             @Environment(EnvType.CLIENT) class Preview extends Base { void tick() {} }",
+        );
+        assert_eq!(classes.len(), 1);
+        assert!(class(&classes, "Base").is_real_class);
+    }
+}
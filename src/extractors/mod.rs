@@ -1,5 +1,18 @@
 pub mod java_extractor;
+pub mod registry_extractor;
 pub mod rust_extractor;
 
 pub use java_extractor::*;
+pub use registry_extractor::*;
 pub use rust_extractor::*;
+
+/// Where `node` sits in the file at `path`
+pub(crate) fn span_of(path: &str, node: tree_sitter::Node) -> crate::SourceSpan {
+    crate::SourceSpan {
+        path: path.to_string(),
+        start_line: node.start_position().row + 1,
+        end_line: node.end_position().row + 1,
+        start_byte: node.start_byte(),
+        end_byte: node.end_byte(),
+    }
+}
@@ -0,0 +1,412 @@
+//! Derive registry id -> vanilla class listings from yarn's registration classes
+//! (`Blocks.java`, `Items.java`, `EntityType.java`), producing the same shape as classes.json.
+
+use crate::provider::{DirectoryProvider, SourceProvider};
+use crate::registry_parser::{BlockEntry, ClassesJson, EntityEntry, ItemEntry};
+use tree_sitter::{Node, Parser, StreamingIterator};
+
+/// Build a `ClassesJson` from the yarn sources rooted at `named_src` (the `namedSrc` directory)
+pub fn extract_registries(named_src: &str) -> ClassesJson {
+    extract_registries_from(&DirectoryProvider::new(named_src))
+}
+
+/// Build a `ClassesJson` from yarn sources rooted at the provider's root
+pub fn extract_registries_from(provider: &dyn SourceProvider) -> ClassesJson {
+    let read = |relative: &str| provider.read(relative).unwrap_or_default();
+
+    ClassesJson {
+        blocks: parse_registrations(&read("net/minecraft/block/Blocks.java"), "Block")
+            .into_iter()
+            .map(|(name, class)| BlockEntry { name, class })
+            .collect(),
+        items: parse_registrations(&read("net/minecraft/item/Items.java"), "Item")
+            .into_iter()
+            .map(|(name, class)| ItemEntry { name, class })
+            .collect(),
+        entities: parse_registrations(&read("net/minecraft/entity/EntityType.java"), "Entity")
+            .into_iter()
+            .map(|(name, class)| EntityEntry { name, class })
+            .collect(),
+    }
+}
+
+/// A method declared in the registration class itself, such as a `register*` overload or a
+/// `create*` factory
+struct Helper {
+    name: String,
+    /// Simple names of the parameter types
+    params: Vec<String>,
+    returns: String,
+    /// The only class its body constructs
+    class: Option<String>,
+}
+
+/// `(registry id, vanilla class)` for every static field initialized by a `register*` call.
+///
+/// The class is resolved from, in order: the type argument of the field type
+/// (`EntityType<ZombieEntity>`), a constructor in the call arguments (`BarrelBlock::new`,
+/// `settings -> new StairsBlock(..)`), a helper of the same file called in the arguments
+/// (`createBlockItemWithUniqueName(..)`), the single constructor used by the helper overload
+/// the call resolves to, and finally the declared field type. Only class names ending in
+/// `class_suffix` are considered, which skips settings and builder types.
+pub fn parse_registrations(content: &str, class_suffix: &str) -> Vec<(String, String)> {
+    if content.is_empty() {
+        return Vec::new();
+    }
+
+    let mut parser = Parser::new();
+    let language = tree_sitter_java::LANGUAGE;
+    parser
+        .set_language(&language.into())
+        .expect("Error loading Java grammar");
+
+    let query_str = r#"
+       (field_declaration
+         type: (_) @type
+         declarator: (variable_declarator
+           name: (identifier) @field
+           value: (method_invocation
+             name: (identifier) @method
+             arguments: (argument_list) @args)))
+    "#;
+
+    let query = tree_sitter::Query::new(&language.into(), query_str).unwrap();
+    let mut cursor = tree_sitter::QueryCursor::new();
+    let source = content.as_bytes();
+    let tree = parser.parse(content, None).unwrap();
+    let helpers = collect_helpers(tree.root_node(), source, class_suffix);
+
+    let mut registrations = Vec::new();
+    let mut matches = cursor.matches(&query, tree.root_node(), source);
+    while let Some(m) = matches.next() {
+        let mut field_type = None;
+        let mut field = "";
+        let mut method = "";
+        let mut args = None;
+
+        for capture in m.captures {
+            match capture.index {
+                0 => field_type = Some(capture.node),
+                1 => field = capture.node.utf8_text(source).unwrap(),
+                2 => method = capture.node.utf8_text(source).unwrap(),
+                3 => args = Some(capture.node),
+                _ => {}
+            }
+        }
+
+        let (Some(field_type), Some(args)) = (field_type, args) else {
+            continue;
+        };
+        if !method.starts_with("register") {
+            continue;
+        }
+
+        let first_arg = args.named_child(0);
+        let registry_id = match first_arg.filter(|a| a.kind() == "string_literal") {
+            Some(literal) => literal
+                .utf8_text(source)
+                .unwrap()
+                .trim_matches('"')
+                .to_string(),
+            None => field.to_lowercase(),
+        };
+
+        let class = type_argument(field_type, source, class_suffix)
+            .or_else(|| {
+                constructed_classes(args, source, class_suffix)
+                    .into_iter()
+                    .next()
+            })
+            .or_else(|| invoked_helper_class(&helpers, args, source))
+            .or_else(|| resolve_helper(&helpers, method, args, source))
+            .or_else(|| {
+                Some(simple_name(field_type.utf8_text(source).unwrap()))
+                    .filter(|name| name.ends_with(class_suffix))
+            });
+
+        if let Some(class) = class {
+            registrations.push((registry_id, class));
+        }
+    }
+
+    registrations
+}
+
+fn collect_helpers(root: Node, source: &[u8], class_suffix: &str) -> Vec<Helper> {
+    let mut helpers = Vec::new();
+    let mut cursor = root.walk();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if node.kind() == "method_declaration" {
+            let text = |field: &str| {
+                node.child_by_field_name(field)
+                    .and_then(|n| n.utf8_text(source).ok())
+                    .unwrap_or_default()
+            };
+            let params = node
+                .child_by_field_name("parameters")
+                .map(|params| {
+                    params
+                        .named_children(&mut cursor)
+                        .filter(|p| p.kind() == "formal_parameter")
+                        .filter_map(|p| p.child_by_field_name("type"))
+                        .map(|t| simple_name(t.utf8_text(source).unwrap()))
+                        .collect()
+                })
+                .unwrap_or_default();
+            let mut classes = node
+                .child_by_field_name("body")
+                .map(|body| constructed_classes(body, source, class_suffix))
+                .unwrap_or_default();
+            classes.sort();
+            classes.dedup();
+            helpers.push(Helper {
+                name: text("name").to_string(),
+                params,
+                returns: simple_name(text("type")),
+                class: (classes.len() == 1).then(|| classes.remove(0)),
+            });
+            continue;
+        }
+        stack.extend(node.named_children(&mut cursor));
+    }
+    helpers
+}
+
+/// Class of the first unqualified helper call among the arguments that resolves to one
+fn invoked_helper_class(helpers: &[Helper], args: Node, source: &[u8]) -> Option<String> {
+    let mut cursor = args.walk();
+    let calls: Vec<Node> = args
+        .named_children(&mut cursor)
+        .filter(|a| a.kind() == "method_invocation" && a.child_by_field_name("object").is_none())
+        .collect();
+    calls.into_iter().find_map(|call| {
+        let name = call.child_by_field_name("name")?.utf8_text(source).ok()?;
+        resolve_helper(
+            helpers,
+            name,
+            call.child_by_field_name("arguments")?,
+            source,
+        )
+    })
+}
+
+/// Class of the helper overload a call resolves to. Overloads are told apart by arity and the
+/// argument types that can be read off the call; `None` unless every remaining overload
+/// constructs the same single class.
+fn resolve_helper(helpers: &[Helper], method: &str, args: Node, source: &[u8]) -> Option<String> {
+    let mut cursor = args.walk();
+    let arg_types: Vec<Option<String>> = args
+        .named_children(&mut cursor)
+        .map(|arg| argument_type(helpers, arg, source))
+        .collect();
+    let candidates: Vec<&Helper> = helpers
+        .iter()
+        .filter(|h| h.name == method && h.params.len() == arg_types.len())
+        .collect();
+
+    let narrowed: Vec<&Helper> = candidates
+        .iter()
+        .copied()
+        .filter(|h| {
+            h.params
+                .iter()
+                .zip(&arg_types)
+                .all(|(param, arg)| arg.as_ref().is_none_or(|arg| arg == param))
+        })
+        .collect();
+    let candidates = if narrowed.is_empty() {
+        candidates
+    } else {
+        narrowed
+    };
+
+    let class = candidates.first()?.class.clone();
+    candidates
+        .iter()
+        .all(|h| h.class == class)
+        .then_some(class)
+        .flatten()
+}
+
+/// Simple name of an argument's type, when the call site tells
+fn argument_type(helpers: &[Helper], arg: Node, source: &[u8]) -> Option<String> {
+    match arg.kind() {
+        "string_literal" => Some("String".to_string()),
+        // `Blocks.STONE` -> Block
+        "field_access" => arg
+            .child_by_field_name("object")
+            .and_then(|o| o.utf8_text(source).ok())
+            .map(|o| o.trim_end_matches('s').to_string()),
+        "object_creation_expression" => arg
+            .child_by_field_name("type")
+            .and_then(|t| t.utf8_text(source).ok())
+            .map(simple_name),
+        "method_reference" | "lambda_expression" => Some("Function".to_string()),
+        // A helper of the same file, when all its overloads agree on the return type
+        "method_invocation" if arg.child_by_field_name("object").is_none() => {
+            let name = arg.child_by_field_name("name")?.utf8_text(source).ok()?;
+            let mut returns = helpers
+                .iter()
+                .filter(|h| h.name == name)
+                .map(|h| &h.returns);
+            let first = returns.next()?;
+            returns.all(|r| r == first).then(|| first.clone())
+        }
+        _ => None,
+    }
+}
+
+/// `ZombieEntity` in `EntityType<ZombieEntity>`
+fn type_argument(field_type: Node, source: &[u8], class_suffix: &str) -> Option<String> {
+    if field_type.kind() != "generic_type" {
+        return None;
+    }
+    let mut cursor = field_type.walk();
+    let arguments = field_type
+        .named_children(&mut cursor)
+        .find(|c| c.kind() == "type_arguments")?;
+    let mut cursor = arguments.walk();
+    arguments
+        .named_children(&mut cursor)
+        .map(|t| simple_name(t.utf8_text(source).unwrap()))
+        .find(|name| name.ends_with(class_suffix))
+}
+
+/// Classes constructed below `node`, via `X::new` or `new X(..)`, in source order
+fn constructed_classes(node: Node, source: &[u8], class_suffix: &str) -> Vec<String> {
+    let mut classes = Vec::new();
+    let mut cursor = node.walk();
+    let mut stack = vec![node];
+    while let Some(node) = stack.pop() {
+        let constructed = match node.kind() {
+            "method_reference" => {
+                let is_constructor = node
+                    .child(node.child_count().saturating_sub(1))
+                    .is_some_and(|last| last.kind() == "new");
+                node.child(0)
+                    .filter(|_| is_constructor)
+                    .and_then(|c| c.utf8_text(source).ok())
+            }
+            "object_creation_expression" => node
+                .child_by_field_name("type")
+                .and_then(|t| t.utf8_text(source).ok()),
+            _ => None,
+        };
+        if let Some(name) = constructed.map(simple_name)
+            && name.ends_with(class_suffix)
+        {
+            classes.push(name);
+        }
+        // Push in reverse so children are visited in source order
+        let children: Vec<Node> = node.named_children(&mut cursor).collect();
+        stack.extend(children.into_iter().rev());
+    }
+    classes
+}
+
+/// `net.minecraft.block.StairsBlock` / `Foo<Bar>` -> `StairsBlock` / `Foo`
+fn simple_name(type_text: &str) -> String {
+    let without_generics = type_text.split('<').next().unwrap_or(type_text);
+    without_generics
+        .rsplit('.')
+        .next()
+        .unwrap_or(without_generics)
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLOCKS: &str = r#"
+public class Blocks {
+    public static final Block STONE = register("stone", StoneBlock::new, AbstractBlock.Settings.create());
+    public static final Block BARREL = register("barrel", settings -> new BarrelBlock(settings), AbstractBlock.Settings.create());
+    public static final Block OAK_STAIRS = registerOldStairsBlock("oak_stairs", OAK_PLANKS);
+    public static final Block DIRT = register("dirt", AbstractBlock.Settings.create());
+
+    private static Block registerOldStairsBlock(String id, Block base) {
+        return register(id, settings -> new StairsBlock(base.getDefaultState(), settings), AbstractBlock.Settings.copyShallow(base));
+    }
+
+    public static Block register(String id, Function<AbstractBlock.Settings, Block> factory, AbstractBlock.Settings settings) {
+        return register(keyOf(id), factory, settings);
+    }
+
+    public static Block register(String id, AbstractBlock.Settings settings) {
+        return register(id, Block::new, settings);
+    }
+}
+"#;
+
+    const ITEMS: &str = r#"
+public class Items {
+    public static final Item STONE = register(Blocks.STONE);
+    public static final Item STICK = register("stick");
+    public static final Item WHEAT_SEEDS = register("wheat_seeds", createBlockItemWithUniqueName(Blocks.WHEAT));
+
+    public static Function<Item.Settings, Item> createBlockItemWithUniqueName(Block block) {
+        return settings -> new BlockItem(block, settings.useItemPrefixedTranslationKey());
+    }
+
+    public static Item register(Block block) {
+        return register(block, BlockItem::new);
+    }
+
+    public static Item register(String id) {
+        return register(keyOf(id), Item::new, new Item.Settings());
+    }
+
+    public static Item register(String id, Function<Item.Settings, Item> factory) {
+        return register(keyOf(id), factory, new Item.Settings());
+    }
+
+    public static Item register(String id, Item.Settings settings) {
+        return register(keyOf(id), Item::new, settings);
+    }
+}
+"#;
+
+    const ENTITY_TYPE: &str = r#"
+public class EntityType<T extends Entity> {
+    public static final EntityType<ZombieEntity> ZOMBIE = register(
+        "zombie", EntityType.Builder.<ZombieEntity>create(ZombieEntity::new, SpawnGroup.MONSTER)
+    );
+}
+"#;
+
+    fn class_of<'a>(registrations: &'a [(String, String)], id: &str) -> Option<&'a str> {
+        registrations
+            .iter()
+            .find(|(registry_id, _)| registry_id == id)
+            .map(|(_, class)| class.as_str())
+    }
+
+    #[test]
+    fn blocks_from_constructors_and_helpers() {
+        let blocks = parse_registrations(BLOCKS, "Block");
+        assert_eq!(class_of(&blocks, "stone"), Some("StoneBlock"));
+        assert_eq!(class_of(&blocks, "barrel"), Some("BarrelBlock"));
+        assert_eq!(class_of(&blocks, "oak_stairs"), Some("StairsBlock"));
+        assert_eq!(class_of(&blocks, "dirt"), Some("Block"));
+    }
+
+    #[test]
+    fn items_resolve_the_matching_overload() {
+        let items = parse_registrations(ITEMS, "Item");
+        assert_eq!(class_of(&items, "stone"), Some("BlockItem"));
+        assert_eq!(class_of(&items, "stick"), Some("Item"));
+        assert_eq!(class_of(&items, "wheat_seeds"), Some("BlockItem"));
+    }
+
+    #[test]
+    fn entities_from_the_type_argument() {
+        let entities = parse_registrations(ENTITY_TYPE, "Entity");
+        assert_eq!(
+            entities,
+            vec![("zombie".to_string(), "ZombieEntity".to_string())]
+        );
+    }
+}
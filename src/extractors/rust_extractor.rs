@@ -1,8 +1,20 @@
-use crate::ClassMethods;
+use super::span_of;
+use crate::{ClassMethods, RustStruct};
 use std::collections::HashMap;
-use tree_sitter::{Parser, StreamingIterator};
+use std::path::Path;
+use tree_sitter::{Node, Parser, StreamingIterator};
 
 pub fn parse_rust_files_unfiltered(path: &str) -> Vec<ClassMethods> {
+    parse_rust_sources(crate::utils::files_with_extension(path, "rs"))
+}
+
+/// Parse Rust sources given as `(path, contents)` pairs, without touching the filesystem
+pub fn parse_rust_sources<I, P, S>(sources: I) -> Vec<ClassMethods>
+where
+    I: IntoIterator<Item = (P, S)>,
+    P: AsRef<Path>,
+    S: AsRef<str>,
+{
     let mut parser = Parser::new();
 
     let language = tree_sitter_rust::LANGUAGE;
@@ -15,46 +27,195 @@ pub fn parse_rust_files_unfiltered(path: &str) -> Vec<ClassMethods> {
          type: (type_identifier) @type_name
          body: (declaration_list
            (function_item
-             name: (identifier) @method_name)))
+             name: (identifier) @method_name) @method)) @impl
     "#;
 
     let query = tree_sitter::Query::new(&language.into(), query_str).unwrap();
     let mut cursor = tree_sitter::QueryCursor::new();
-    let mut classes = HashMap::<String, Vec<String>>::new();
+    let mut classes = HashMap::<String, ClassMethods>::new();
 
-    crate::utils::walk_files(path, "rs", |_path, content| {
-        let tree = parser.parse(&content, None).unwrap();
+    for (path, content) in sources {
+        let path = path.as_ref().to_string_lossy().replace('\\', "/");
+        let content = content.as_ref();
+        let tree = parser.parse(content, None).unwrap();
         let mut matches = cursor.matches(&query, tree.root_node(), content.as_bytes());
 
         while let Some(m) = matches.next() {
             let mut class_name = String::new();
             let mut method_name = String::new();
+            let mut vanilla_methods = Vec::new();
+            let mut vanilla_classes = Vec::new();
+            let mut span = None;
 
             for capture in m.captures {
                 let text = capture.node.utf8_text(content.as_bytes()).unwrap();
                 match capture.index {
                     0 => class_name = text.to_string(),
                     1 => method_name = text.to_string(),
+                    2 => {
+                        vanilla_methods = vanilla_markers(capture.node, content.as_bytes());
+                        span = Some(span_of(&path, capture.node));
+                    }
+                    3 => vanilla_classes = vanilla_markers(capture.node, content.as_bytes()),
                     _ => {}
                 }
             }
 
             if !class_name.is_empty() && !method_name.is_empty() {
-                let methods = classes.entry(class_name).or_default();
-                if !methods.contains(&method_name) {
-                    methods.push(method_name);
+                let class = classes
+                    .entry(class_name.clone())
+                    .or_insert_with(|| ClassMethods {
+                        class_name,
+                        is_real_class: true,
+                        ..Default::default()
+                    });
+                if !class.methods.contains(&method_name) {
+                    class.methods.push(method_name.clone());
+                }
+                if let Some(span) = span {
+                    class
+                        .method_spans
+                        .entry(method_name.clone())
+                        .or_default()
+                        .push(span);
+                }
+                for vanilla_method in vanilla_methods {
+                    class
+                        .vanilla_methods
+                        .insert(vanilla_method, method_name.clone());
+                }
+                for vanilla_class in vanilla_classes {
+                    if !class.vanilla_classes.contains(&vanilla_class) {
+                        class.vanilla_classes.push(vanilla_class);
+                    }
                 }
             }
         }
-    });
-
-    classes
-        .into_iter()
-        .map(|(class_name, methods)| ClassMethods {
-            class_name,
-            class_type: String::new(),
-            methods,
-            is_real_class: true,
-        })
-        .collect()
+    }
+
+    classes.into_values().collect()
+}
+
+/// Every struct declared in the given `(path, contents)` pairs, generic or not, with or without
+/// methods
+pub fn parse_rust_structs<I, P, S>(sources: I) -> Vec<RustStruct>
+where
+    I: IntoIterator<Item = (P, S)>,
+    P: AsRef<Path>,
+    S: AsRef<str>,
+{
+    let mut parser = Parser::new();
+    let language = tree_sitter_rust::LANGUAGE;
+    parser
+        .set_language(&language.into())
+        .expect("Error loading Rust grammar");
+    let query = tree_sitter::Query::new(
+        &language.into(),
+        "(struct_item name: (type_identifier) @name) @struct",
+    )
+    .unwrap();
+    let mut cursor = tree_sitter::QueryCursor::new();
+
+    let mut structs = Vec::new();
+    for (path, content) in sources {
+        let path = path.as_ref().to_string_lossy().replace('\\', "/");
+        let content = content.as_ref();
+        let tree = parser.parse(content, None).unwrap();
+        let mut matches = cursor.matches(&query, tree.root_node(), content.as_bytes());
+        while let Some(m) = matches.next() {
+            let capture = |index| m.captures.iter().find(|c| c.index == index);
+            let (Some(name), Some(item)) = (capture(0), capture(1)) else {
+                continue;
+            };
+            structs.push(RustStruct {
+                name: name.node.utf8_text(content.as_bytes()).unwrap().to_string(),
+                category: String::new(),
+                span: span_of(&path, item.node),
+                vanilla_classes: vanilla_markers(item.node, content.as_bytes()),
+            });
+        }
+    }
+    structs
+}
+
+/// Vanilla names an item is explicitly marked as implementing, from the doc comments and
+/// attributes directly above it: `/// vanilla: onStateReplaced` or `#[vanilla(onStateReplaced)]`.
+/// Several names may be given, comma separated.
+fn vanilla_markers(item: Node, source: &[u8]) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut sibling = item.prev_sibling();
+    while let Some(node) = sibling {
+        if !matches!(
+            node.kind(),
+            "line_comment" | "block_comment" | "attribute_item"
+        ) {
+            break;
+        }
+        if let Some(marked) = node.utf8_text(source).ok().and_then(parse_marker) {
+            names.extend(marked);
+        }
+        sibling = node.prev_sibling();
+    }
+    names
+}
+
+fn parse_marker(text: &str) -> Option<Vec<String>> {
+    let text = text.trim();
+    let body = if let Some(doc) = text.strip_prefix("///") {
+        doc.trim().strip_prefix("vanilla:")?
+    } else if let Some(attribute) = text.strip_prefix("#[") {
+        attribute
+            .strip_suffix(']')?
+            .trim()
+            .strip_prefix("vanilla")?
+            .trim()
+            .strip_prefix('(')?
+            .strip_suffix(')')?
+    } else {
+        return None;
+    };
+    Some(
+        body.split(',')
+            .map(|name| name.trim().trim_matches('"').to_string())
+            .filter(|name| !name.is_empty())
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn structs_without_methods_and_generic_structs() {
+        let source = "pub struct Empty;\n\
+                      impl BlockBehaviour for Empty {}\n\
+                      pub struct Wrapper<T> { inner: T }\n\
+                      /// vanilla: ChestBlock\n\
+                      pub struct Chest;\n";
+        let structs = parse_rust_structs([("blocks/mod.rs", source)]);
+        let names: Vec<&str> = structs.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["Empty", "Wrapper", "Chest"]);
+        assert_eq!(structs[1].span.start_line, 3);
+        assert_eq!(structs[2].vanilla_classes, ["ChestBlock"]);
+        assert!(parse_rust_sources([("blocks/mod.rs", source)]).is_empty());
+    }
+
+    #[test]
+    fn vanilla_markers_in_doc_comments_and_attributes() {
+        let names = |text| parse_marker(text).unwrap_or_default();
+        assert_eq!(names("/// vanilla: onUse"), ["onUse"]);
+        assert_eq!(
+            names("/// vanilla: onUse, onUseWithItem"),
+            ["onUse", "onUseWithItem"]
+        );
+        assert_eq!(names("#[vanilla(onStateReplaced)]"), ["onStateReplaced"]);
+        assert_eq!(
+            names("#[vanilla(\"BarrelBlock\", ChestBlock)]"),
+            ["BarrelBlock", "ChestBlock"]
+        );
+        assert!(parse_marker("/// Opens the barrel").is_none());
+        assert!(parse_marker("#[derive(Debug)]").is_none());
+        assert!(parse_marker("// vanilla: onUse").is_none());
+    }
 }
@@ -0,0 +1,197 @@
+//! Detection of generic Steel behaviors standing in for specialized vanilla classes.
+
+use crate::registry_parser::ClassMapping;
+use crate::utils;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+
+/// Fields missing from a config file take their shipped value
+#[derive(Debug, Clone, Deserialize)]
+pub struct FallbackConfig {
+    /// Steel behaviors always treated as generic
+    #[serde(default = "shipped_generic_behaviors")]
+    pub generic_behaviors: Vec<String>,
+    /// A behavior wired to at least this many distinct, unrelated vanilla classes is inferred
+    /// generic
+    #[serde(default = "shipped_min_vanilla_classes")]
+    pub min_vanilla_classes: usize,
+    /// Superclasses too general to make the classes below them related
+    #[serde(default = "shipped_root_classes")]
+    pub root_classes: Vec<String>,
+}
+
+/// The settings shipped in `config/generic_fallbacks.json`
+impl Default for FallbackConfig {
+    fn default() -> Self {
+        serde_json::from_str(include_str!("../config/generic_fallbacks.json"))
+            .expect("config/generic_fallbacks.json is valid")
+    }
+}
+
+fn shipped_generic_behaviors() -> Vec<String> {
+    FallbackConfig::default().generic_behaviors
+}
+
+fn shipped_min_vanilla_classes() -> usize {
+    FallbackConfig::default().min_vanilla_classes
+}
+
+fn shipped_root_classes() -> Vec<String> {
+    FallbackConfig::default().root_classes
+}
+
+impl FallbackConfig {
+    /// Load the config, falling back to defaults when the file does not exist
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        utils::load_json_or_default(path)
+    }
+}
+
+/// Steel behaviors that are configured or inferred to be generic fallbacks. `superclasses`
+/// maps vanilla classes to the class they extend; a behavior whose classes all share a
+/// superclass outside `root_classes` is a shared port of that hierarchy, not a fallback.
+pub fn detect_generic_behaviors(
+    config: &FallbackConfig,
+    mappings: &[&ClassMapping],
+    superclasses: &HashMap<&str, &str>,
+) -> HashSet<String> {
+    let mut vanilla_classes: HashMap<&str, HashSet<&str>> = HashMap::new();
+    for mapping in mappings {
+        for (vanilla_class, bindings) in mapping.iter() {
+            for binding in bindings {
+                vanilla_classes
+                    .entry(binding.steel_behavior.as_str())
+                    .or_default()
+                    .insert(vanilla_class.as_str());
+            }
+        }
+    }
+
+    let mut generic: HashSet<String> = config.generic_behaviors.iter().cloned().collect();
+    for (behavior, classes) in vanilla_classes {
+        if classes.len() >= config.min_vanilla_classes
+            && !share_superclass(config, superclasses, &classes)
+        {
+            generic.insert(behavior.to_string());
+        }
+    }
+    generic
+}
+
+fn share_superclass(
+    config: &FallbackConfig,
+    superclasses: &HashMap<&str, &str>,
+    classes: &HashSet<&str>,
+) -> bool {
+    let ancestors = |class: &str| {
+        let mut ancestors = HashSet::new();
+        let mut next = superclasses.get(class);
+        while let Some(&parent) = next {
+            if config.root_classes.iter().any(|r| r == parent) || !ancestors.insert(parent) {
+                break;
+            }
+            next = superclasses.get(parent);
+        }
+        ancestors
+    };
+    let mut classes = classes.iter();
+    let Some(first) = classes.next() else {
+        return false;
+    };
+    let common = classes.fold(ancestors(first), |common, class| {
+        common.intersection(&ancestors(class)).copied().collect()
+    });
+    !common.is_empty()
+}
+
+/// Whether `steel_behavior` is a generic fallback for `vanilla_class` rather than its own port.
+/// A generic behavior still counts as the real port of the class it is named after
+/// (`BlockItemBehavior` for `BlockItem`).
+pub fn is_generic_fallback(
+    generic: &HashSet<String>,
+    steel_behavior: &str,
+    vanilla_class: &str,
+) -> bool {
+    if !generic.contains(steel_behavior) {
+        return false;
+    }
+    let own_name = steel_behavior
+        .strip_suffix("Behavior")
+        .or_else(|| steel_behavior.strip_suffix("Behaviour"))
+        .unwrap_or(steel_behavior);
+    own_name != vanilla_class && steel_behavior != vanilla_class
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry_parser::BehaviorBinding;
+
+    fn mapping(behavior: &str, classes: &[&str]) -> ClassMapping {
+        classes
+            .iter()
+            .map(|class| {
+                let binding = BehaviorBinding {
+                    steel_behavior: behavior.to_string(),
+                    registry_ids: vec![format!("minecraft:{}", class.to_lowercase())],
+                };
+                (class.to_string(), vec![binding])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn siblings_of_one_hierarchy_are_not_generic() {
+        let superclasses = HashMap::from([
+            ("SignBlock", "AbstractSignBlock"),
+            ("WallSignBlock", "AbstractSignBlock"),
+            ("HangingSignBlock", "AbstractSignBlock"),
+            ("AbstractSignBlock", "BlockWithEntity"),
+            ("BlockWithEntity", "Block"),
+        ]);
+        let signs = mapping(
+            "SignBlock",
+            &["SignBlock", "WallSignBlock", "HangingSignBlock"],
+        );
+        let generic =
+            detect_generic_behaviors(&FallbackConfig::default(), &[&signs], &superclasses);
+        assert!(!generic.contains("SignBlock"));
+    }
+
+    #[test]
+    fn unrelated_classes_make_a_behavior_generic() {
+        let superclasses = HashMap::from([
+            ("StairsBlock", "Block"),
+            ("SlabBlock", "Block"),
+            ("FenceBlock", "HorizontalConnectingBlock"),
+            ("HorizontalConnectingBlock", "Block"),
+        ]);
+        let default = mapping("DefaultBlock", &["StairsBlock", "SlabBlock", "FenceBlock"]);
+        let generic =
+            detect_generic_behaviors(&FallbackConfig::default(), &[&default], &superclasses);
+        assert!(generic.contains("DefaultBlock"));
+    }
+
+    #[test]
+    fn behavior_named_after_the_class_is_its_own_port() {
+        let generic = HashSet::from(["BlockItemBehavior".to_string()]);
+        assert!(!is_generic_fallback(
+            &generic,
+            "BlockItemBehavior",
+            "BlockItem"
+        ));
+        assert!(is_generic_fallback(
+            &generic,
+            "BlockItemBehavior",
+            "BowItem"
+        ));
+    }
+
+    #[test]
+    fn missing_fields_take_the_shipped_values() {
+        let config: FallbackConfig =
+            serde_json::from_str(r#"{ "min_vanilla_classes": 5 }"#).unwrap();
+        assert_eq!(config.min_vanilla_classes, 5);
+        assert_eq!(config.root_classes, FallbackConfig::default().root_classes);
+    }
+}
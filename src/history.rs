@@ -0,0 +1,174 @@
+//! Coverage over a range of SteelMC commits, read from git objects without checking out.
+
+use crate::analysis::{self, Sources};
+use crate::config::Config;
+use crate::coverage::{self, CategorySummary, Coverage};
+use crate::provider::{self, GitProvider};
+use crate::{AnalysisResult, ClassTracking, Error};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Commit {
+    pub hash: String,
+    /// Committer date, ISO 8601
+    pub date: String,
+    pub subject: String,
+}
+
+/// Coverage at one commit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesPoint {
+    pub commit: Commit,
+    pub implemented_methods: usize,
+    pub tracked_methods: usize,
+    pub percentage_implemented: f32,
+    pub weighted_percentage_implemented: f32,
+    pub categories: Vec<CategorySummary>,
+    /// Generated registration files missing at this commit and taken from the working tree, so
+    /// registry-derived tracking reflects today's registrations rather than this commit's
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub generated_from_working_tree: Vec<String>,
+}
+
+/// The earliest commit in the range at which a method was implemented
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FirstImplementation {
+    pub class_name: String,
+    pub method_name: String,
+    pub commit: String,
+    pub date: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct History {
+    pub series: Vec<SeriesPoint>,
+    pub first_implemented: Vec<FirstImplementation>,
+}
+
+/// Commits in `range` (e.g. `A..B`), oldest first. `repo` may be any directory in the repository.
+pub fn commits_in_range(repo: &str, range: &str) -> Result<Vec<Commit>, Error> {
+    let log = provider::git(
+        Path::new(repo),
+        &["log", "--reverse", "--format=%H%x09%cI%x09%s", range],
+    )
+    .ok_or_else(|| Error::MissingSource(format!("git range {range} in {repo}")))?;
+
+    Ok(log
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            Some(Commit {
+                hash: fields.next()?.to_string(),
+                date: fields.next()?.to_string(),
+                subject: fields.next().unwrap_or_default().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Commits `range` excludes but builds on, e.g. `A` for `A..B`. Empty when the range reaches
+/// the root commit.
+pub fn range_base(repo: &str, range: &str) -> Result<Vec<String>, Error> {
+    let boundary = provider::git(Path::new(repo), &["rev-list", "--boundary", range])
+        .ok_or_else(|| Error::MissingSource(format!("git range {range} in {repo}")))?;
+    Ok(boundary
+        .lines()
+        .filter_map(|line| line.strip_prefix('-'))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Analyze `config.steel_core` at a git revision, reusing the yarn side of `sources`
+pub fn analyze_revision(
+    config: &Config,
+    sources: &mut Sources,
+    revision: &str,
+) -> Result<AnalysisResult, Error> {
+    let steel = GitProvider::new(&config.steel_core, revision)?;
+    analysis::load_steel_sources(config, &steel, sources);
+    let mappings = analysis::build_mappings(config, sources);
+    Ok(analysis::compute_tracking(config, sources, &mappings))
+}
+
+/// Run the Steel side of the analysis at every commit of `range` in the repository holding
+/// `config.steel_core`. Yarn is parsed once; `on_snapshot` sees each commit's full result.
+pub fn backfill(
+    config: &Config,
+    range: &str,
+    mut on_snapshot: impl FnMut(&Commit, &AnalysisResult),
+) -> Result<History, Error> {
+    let commits = commits_in_range(&config.steel_core, range)?;
+    let mut sources: Sources = analysis::load_yarn_sources(config)?;
+
+    let mut series = Vec::new();
+    let mut first_implemented = Vec::new();
+    // Methods already implemented before the range are not credited to its first commit
+    let mut seen: HashSet<(String, String)> = HashSet::new();
+    for base in range_base(&config.steel_core, range)? {
+        let result = analyze_revision(config, &mut sources, &base)?;
+        seen.extend(implemented_methods(&result));
+    }
+
+    for commit in commits {
+        let result = analyze_revision(config, &mut sources, &commit.hash)?;
+        on_snapshot(&commit, &result);
+
+        let mut raw = Coverage::default();
+        let mut weighted = Coverage::default();
+        for class in tracked_classes(&result) {
+            raw.merge(Coverage::raw(&class.methods));
+            weighted.merge(Coverage::weighted(&class.methods));
+        }
+
+        let mut implemented = implemented_methods(&result);
+        implemented.sort();
+        for key in implemented {
+            if seen.insert(key.clone()) {
+                first_implemented.push(FirstImplementation {
+                    class_name: key.0,
+                    method_name: key.1,
+                    commit: commit.hash.clone(),
+                    date: commit.date.clone(),
+                });
+            }
+        }
+
+        series.push(SeriesPoint {
+            implemented_methods: raw.implemented as usize,
+            tracked_methods: raw.total as usize,
+            percentage_implemented: raw.percentage(),
+            weighted_percentage_implemented: weighted.percentage(),
+            categories: coverage::summarize(&result),
+            generated_from_working_tree: sources.generated_from_working_tree.clone(),
+            commit,
+        });
+    }
+
+    Ok(History {
+        series,
+        first_implemented,
+    })
+}
+
+/// Fallback classes are left out, as in the category summaries
+pub(crate) fn tracked_classes(result: &AnalysisResult) -> impl Iterator<Item = &ClassTracking> {
+    result
+        .classes
+        .iter()
+        .filter(|c| c.generic_fallback.is_none())
+}
+
+/// `(class, method)` of every implemented method of the tracked classes
+fn implemented_methods(result: &AnalysisResult) -> Vec<(String, String)> {
+    tracked_classes(result)
+        .flat_map(|class| {
+            class
+                .methods
+                .iter()
+                .filter(|m| m.status.is_implemented())
+                .map(|m| (class.class_name.clone(), m.method_name.clone()))
+        })
+        .collect()
+}
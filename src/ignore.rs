@@ -0,0 +1,79 @@
+//! Allowlist of vanilla classes and methods Steel intentionally does not port.
+
+use crate::utils;
+use crate::{IgnoreKind, IgnoreReason};
+use serde::{Deserialize, Serialize};
+
+/// Marks a whole class, or a single method of it, as intentionally omitted.
+/// `class` may be `*` to match the method on every class.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IgnoreEntry {
+    pub class: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    pub kind: IgnoreKind,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IgnoreList {
+    pub entries: Vec<IgnoreEntry>,
+}
+
+impl IgnoreList {
+    /// Load the ignore file, treating a missing file as an empty list
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        utils::load_json_or_default(path)
+    }
+
+    /// The most specific entry covering `class.method`: an exact method entry, then a
+    /// wildcard method entry, then a whole-class entry
+    pub fn lookup(&self, class: &str, method: &str) -> Option<IgnoreReason> {
+        let method_entry = |class_pattern: &str| {
+            self.entries
+                .iter()
+                .find(|e| e.class == class_pattern && e.method.as_deref() == Some(method))
+        };
+        method_entry(class)
+            .or_else(|| method_entry("*"))
+            .or_else(|| {
+                self.entries
+                    .iter()
+                    .find(|e| e.class == class && e.method.is_none())
+            })
+            .map(|e| IgnoreReason {
+                kind: e.kind,
+                reason: e.reason.clone(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(class: &str, method: Option<&str>, reason: &str) -> IgnoreEntry {
+        IgnoreEntry {
+            class: class.to_string(),
+            method: method.map(str::to_string),
+            kind: IgnoreKind::NotApplicable,
+            reason: reason.to_string(),
+        }
+    }
+
+    #[test]
+    fn most_specific_entry_wins() {
+        let list = IgnoreList {
+            entries: vec![
+                entry("BarrelBlock", None, "class"),
+                entry("*", Some("tick"), "wildcard"),
+                entry("BarrelBlock", Some("tick"), "exact"),
+            ],
+        };
+        let reason = |class, method| list.lookup(class, method).map(|r| r.reason);
+        assert_eq!(reason("BarrelBlock", "tick").as_deref(), Some("exact"));
+        assert_eq!(reason("ChestBlock", "tick").as_deref(), Some("wildcard"));
+        assert_eq!(reason("BarrelBlock", "onUse").as_deref(), Some("class"));
+        assert_eq!(reason("ChestBlock", "onUse"), None);
+    }
+}
@@ -1,8 +1,30 @@
+pub mod analysis;
+pub mod blame;
+pub mod changelog;
+pub mod classify;
+pub mod config;
+pub mod coverage;
+mod error;
 pub mod extractors;
+pub mod fallback;
+pub mod history;
+pub mod ignore;
+pub mod lookup;
+pub mod mappings;
+pub mod matching;
+pub mod profile;
+pub mod provider;
 pub mod registry_parser;
+pub mod show;
 pub mod types;
+pub mod upgrade;
 pub mod utils;
+pub mod verified;
 
+pub use analysis::*;
+pub use config::Config;
+pub use coverage::*;
+pub use error::Error;
 pub use extractors::*;
 pub use types::*;
 pub use utils::*;
@@ -0,0 +1,205 @@
+//! Reverse lookup: which vanilla classes and registry ids a Steel behavior stands in for, and
+//! which behaviors stand in for nothing.
+
+use crate::analysis::{self, Sources};
+use crate::config::Config;
+use crate::coverage::Coverage;
+use crate::registry_parser;
+use crate::{AnalysisResult, MethodTracking};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// One vanilla class backed by the behavior
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VanillaUsage {
+    pub vanilla_class: String,
+    /// `block` or `item` for registry entries, otherwise the class category
+    pub kind: String,
+    /// Registry ids of this class registered with the behavior; empty for classes matched by
+    /// name or marker
+    pub registry_ids: Vec<String>,
+    pub implemented_methods: usize,
+    pub tracked_methods: usize,
+    pub percentage_implemented: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BehaviorUsage {
+    /// As spelled in Steel, whatever the case of the lookup
+    pub steel_behavior: String,
+    pub vanilla_classes: Vec<VanillaUsage>,
+    /// Registered with the behavior but unknown to classes.json
+    pub unknown_registry_ids: Vec<String>,
+    /// Over the tracked methods of every vanilla class, each class counted once
+    pub percentage_implemented: Option<f32>,
+    /// Every registry id counts once, scaled by the coverage of its class
+    pub usage_weighted_percentage: Option<f32>,
+}
+
+impl BehaviorUsage {
+    pub fn registry_ids(&self) -> usize {
+        self.vanilla_classes
+            .iter()
+            .map(|c| c.registry_ids.len())
+            .sum::<usize>()
+            + self.unknown_registry_ids.len()
+    }
+}
+
+/// Invert the registry mapping and the class tracking for `steel_behavior`, matched
+/// case-insensitively
+pub fn behavior_usage(
+    config: &Config,
+    sources: &Sources,
+    result: &AnalysisResult,
+    steel_behavior: &str,
+) -> BehaviorUsage {
+    let is_behavior = |name: &str| name.eq_ignore_ascii_case(steel_behavior);
+
+    // Registry entries are judged against this behavior, one usage per (kind, class)
+    let mut by_class: BTreeMap<(String, String), (Vec<String>, Vec<MethodTracking>)> =
+        BTreeMap::new();
+    for entry in analysis::registry_report(config, sources) {
+        if !entry.steel_behavior.as_deref().is_some_and(is_behavior) {
+            continue;
+        }
+        let usage = by_class
+            .entry((entry.kind, entry.vanilla_class))
+            .or_insert_with(|| (Vec::new(), entry.methods));
+        usage.0.push(entry.registry_id);
+    }
+
+    // Classes reaching the behavior through name matching or explicit markers
+    for class in &result.classes {
+        if !class.rust_class.as_deref().is_some_and(is_behavior) {
+            continue;
+        }
+        let registered = by_class
+            .keys()
+            .any(|(_, vanilla_class)| *vanilla_class == class.class_name);
+        if !registered {
+            by_class.insert(
+                (class.class_type.clone(), class.class_name.clone()),
+                (Vec::new(), class.methods.clone()),
+            );
+        }
+    }
+
+    let mut raw = Coverage::default();
+    let mut usage = Coverage::default();
+    let mut vanilla_classes = Vec::new();
+    for ((kind, vanilla_class), (registry_ids, methods)) in by_class {
+        let coverage = Coverage::raw(&methods);
+        raw.merge(coverage);
+        if coverage.total > 0.0 {
+            let ids = registry_ids.len() as f32;
+            usage.merge(Coverage {
+                implemented: ids * coverage.percentage() / 100.0,
+                total: ids,
+            });
+        }
+        vanilla_classes.push(VanillaUsage {
+            vanilla_class,
+            kind,
+            registry_ids,
+            implemented_methods: coverage.implemented as usize,
+            tracked_methods: coverage.total as usize,
+            percentage_implemented: (coverage.total > 0.0).then(|| coverage.percentage()),
+        });
+    }
+
+    // Registrations the join with classes.json could not place
+    let known: Vec<&String> = vanilla_classes
+        .iter()
+        .flat_map(|c| &c.registry_ids)
+        .collect();
+    let mut unknown_registry_ids: Vec<String> = sources
+        .steel_blocks
+        .iter()
+        .chain(&sources.steel_items)
+        .filter(|(_, behavior)| is_behavior(behavior))
+        .map(|(id, _)| registry_parser::namespaced(id))
+        .filter(|id| !known.contains(&id))
+        .collect();
+    unknown_registry_ids.sort();
+    unknown_registry_ids.dedup();
+
+    let canonical = sources
+        .rust_classes
+        .iter()
+        .map(|c| &c.class_name)
+        .chain(sources.steel_blocks.values())
+        .chain(sources.steel_items.values())
+        .find(|name| is_behavior(name))
+        .map_or(steel_behavior, String::as_str);
+
+    BehaviorUsage {
+        steel_behavior: canonical.to_string(),
+        vanilla_classes,
+        unknown_registry_ids,
+        percentage_implemented: (raw.total > 0.0).then(|| raw.percentage()),
+        usage_weighted_percentage: (usage.total > 0.0).then(|| usage.percentage()),
+    }
+}
+
+/// A Steel block or item behavior nothing in the registries or the tracking points at
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrphanedBehavior {
+    pub steel_behavior: String,
+    pub kind: String,
+    /// File defining it, relative to steel-core
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+/// Structs and impl'd types under the block and item behavior folders that no generated
+/// registration references, that map to no vanilla class and carry no vanilla marker
+pub fn orphaned_behaviors(sources: &Sources, result: &AnalysisResult) -> Vec<OrphanedBehavior> {
+    let marked = sources
+        .rust_classes
+        .iter()
+        .filter(|c| !c.vanilla_classes.is_empty())
+        .map(|c| &c.class_name)
+        .chain(
+            sources
+                .rust_structs
+                .iter()
+                .filter(|s| !s.vanilla_classes.is_empty())
+                .map(|s| &s.name),
+        );
+    let used: HashSet<String> = sources
+        .steel_blocks
+        .values()
+        .chain(sources.steel_items.values())
+        .chain(result.classes.iter().filter_map(|c| c.rust_class.as_ref()))
+        .chain(marked)
+        .map(|b| b.to_lowercase())
+        .collect();
+
+    let structs = sources
+        .rust_structs
+        .iter()
+        .map(|s| (&s.name, &s.category, Some(&s.span.path)));
+    // Types implemented without a struct of their own, such as enums
+    let impl_only = sources.rust_classes.iter().map(|c| {
+        let path = c.method_spans.values().flatten().map(|s| &s.path).min();
+        (&c.class_name, &c.class_type, path)
+    });
+
+    let mut orphaned: Vec<OrphanedBehavior> = Vec::new();
+    for (name, kind, path) in structs.chain(impl_only) {
+        if !matches!(kind.as_str(), "block" | "item")
+            || used.contains(&name.to_lowercase())
+            || orphaned.iter().any(|o| o.steel_behavior == *name)
+        {
+            continue;
+        }
+        orphaned.push(OrphanedBehavior {
+            steel_behavior: name.clone(),
+            kind: kind.clone(),
+            path: path.cloned(),
+        });
+    }
+    orphaned.sort_by(|a, b| (&a.kind, &a.steel_behavior).cmp(&(&b.kind, &b.steel_behavior)));
+    orphaned
+}
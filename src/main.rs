@@ -1,375 +1,586 @@
-use std::collections::HashMap;
+use serde::Serialize;
 use std::fs;
-use steel_tracker::extractors::{parse_java_files_unfiltered, parse_rust_files_unfiltered};
-use steel_tracker::registry_parser::{self, ClassesJson};
-use steel_tracker::types::{
-    AnalysisResult, ClassMethods, ClassTracking, ImplementationStatus, MethodTracking,
+use steel_tracker::coverage::{self, Coverage};
+use steel_tracker::types::{ClassTracking, ImplementationStatus, MatchSource};
+use steel_tracker::upgrade::ClaimChange;
+use steel_tracker::{
+    AnalysisResult, Config, Error, analysis, blame, changelog, history, lookup, provider, show,
+    upgrade,
 };
 
 fn main() {
-    fs::create_dir_all("outputs").expect("Failed to create outputs directory");
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    // `--profile <name>` selects config/profiles/<name>.json and writes to outputs/<name>
+    let mut config = match take_value(&mut args, "--profile") {
+        Some(profile) => Config::from_profile("config", &profile),
+        None => Config::from_config_dir("config"),
+    }
+    .unwrap_or_else(|e| exit_with(e));
+    fs::create_dir_all(&config.output_dir).expect("Failed to create outputs directory");
+
+    match args.first().map(String::as_str) {
+        Some("history") => {
+            args.remove(0);
+            run_history(config, args);
+        }
+        Some("behavior") => {
+            args.remove(0);
+            run_behavior(config, args);
+        }
+        Some("show") => {
+            args.remove(0);
+            run_show(config, args);
+        }
+        Some("verify") => {
+            args.remove(0);
+            run_verify(config, args);
+        }
+        Some("upgrade") => {
+            args.remove(0);
+            run_upgrade(config, args);
+        }
+        Some("changelog") => {
+            args.remove(0);
+            run_changelog(config, args);
+        }
+        _ => {
+            let blame = take_flag(&mut args, "--blame");
+            apply_source_args(&mut config, args);
+            run_analysis(&config, blame);
+        }
+    }
+}
 
-    // Load classes.json for registry-based mapping
-    let classes_json = ClassesJson::load("sources/SteelMC/steel-core/build/classes.json")
-        .expect("Failed to load classes.json");
+/// The full analysis; `--blame` attributes implemented methods to Steel contributors
+fn run_analysis(config: &Config, blame: bool) {
+    let out = &config.output_dir;
+    let sources = analysis::load_sources(config).unwrap_or_else(|e| exit_with(e));
 
-    // Parse generated registration code
-    let steel_blocks = registry_parser::parse_generated_blocks(
-        "sources/SteelMC/steel-core/src/behavior/generated/blocks.rs",
+    write_json(
+        &format!("{out}/classes_from_yarn.json"),
+        &sources.yarn_registries,
     );
-    let steel_items = registry_parser::parse_generated_items(
-        "sources/SteelMC/steel-core/src/behavior/generated/items.rs",
+    println!(
+        "Wrote {out}/classes_from_yarn.json ({} blocks, {} items, {} entities)",
+        sources.yarn_registries.blocks.len(),
+        sources.yarn_registries.items.len(),
+        sources.yarn_registries.entities.len()
+    );
+    match &sources.registry_mismatches {
+        Some(mismatches) => {
+            write_json(&format!("{out}/registry_cross_check.json"), mismatches);
+            println!(
+                "Wrote {out}/registry_cross_check.json ({} mismatches between classes.json and yarn)",
+                mismatches.len()
+            );
+        }
+        None => println!("No classes.json found, using registry mapping derived from yarn"),
+    }
+
+    let mappings = analysis::build_mappings(config, &sources);
+
+    // Vanilla classes wired inconsistently across their registry ids
+    write_json(&format!("{out}/conflicts.json"), &mappings.conflicts);
+    println!(
+        "Wrote {out}/conflicts.json ({} conflicting classes)",
+        mappings.conflicts.len()
     );
+    if !mappings.conflicts.is_empty() {
+        println!("  Non-primary behaviors are judged separately under secondary_behaviors");
+    }
 
-    // Build vanilla_class -> steel_behavior mapping
-    let (block_class_map, item_class_map) =
-        registry_parser::build_class_mapping(&classes_json, &steel_blocks, &steel_items);
+    // Registry ids missing on either side of the join
+    let gaps = &mappings.registration_gaps;
+    write_json(&format!("{out}/registration_gaps.json"), gaps);
+    println!("Wrote {out}/registration_gaps.json");
+    println!(
+        "  Unregistered in Steel: {} blocks, {} items",
+        gaps.unregistered_blocks.len(),
+        gaps.unregistered_items.len()
+    );
+    println!(
+        "  Unknown to classes.json: {} blocks, {} items",
+        gaps.unknown_blocks.len(),
+        gaps.unknown_items.len()
+    );
 
-    // Parse all Java classes from entity, block, and item folders
-    let mut all_java_classes: Vec<ClassMethods> = Vec::new();
+    write_json(&format!("{out}/java.json"), &sources.java_classes);
+    println!(
+        "Wrote {out}/java.json ({} classes)",
+        sources.java_classes.len()
+    );
 
-    // Blocks
-    let block_classes =
-        parse_java_files_unfiltered("sources/yarn/build/namedSrc/net/minecraft/block");
-    for mut c in block_classes {
-        c.class_type = "block".to_string();
-        all_java_classes.push(c);
+    // Warn about methods Steel doesn't have yet
+    println!("\n=== Methods Steel Needs to Implement ===");
+    for (table_name, table) in &config.method_tables.tables {
+        for (java_method, rust_method) in table {
+            if rust_method.is_none() {
+                println!("  {}: {} (not in Steel yet)", table_name, java_method);
+            }
+        }
     }
 
-    // Items
-    let item_classes =
-        parse_java_files_unfiltered("sources/yarn/build/namedSrc/net/minecraft/item");
-    for mut c in item_classes {
-        c.class_type = "item".to_string();
-        all_java_classes.push(c);
+    let mut result = analysis::compute_tracking(config, &sources, &mappings);
+    if blame {
+        blame::attribute(
+            &mut result,
+            &config.steel_core,
+            config.steel_revision.as_deref(),
+        );
+        let contributors = blame::contributor_summary(&result);
+        write_json(&format!("{out}/contributors.json"), &contributors);
+        println!(
+            "Wrote {out}/contributors.json ({} contributors)",
+            contributors.len()
+        );
+        for contributor in contributors.iter().take(10) {
+            println!(
+                "  {} <{}>: {} functions across {} classes in {} commits",
+                contributor.author,
+                contributor.email,
+                contributor.methods,
+                contributor.classes,
+                contributor.commits
+            );
+        }
     }
 
-    // Entities and AI (with sub-type detection)
-    let entity_classes =
-        parse_java_files_unfiltered("sources/yarn/build/namedSrc/net/minecraft/entity");
-    for mut c in entity_classes {
-        c.class_type = detect_entity_subtype(&c.class_name);
-        all_java_classes.push(c);
+    // Behaviors nothing registers or maps to, likely dead or forgotten
+    let orphaned = lookup::orphaned_behaviors(&sources, &result);
+    write_json(&format!("{out}/orphaned_behaviors.json"), &orphaned);
+    println!(
+        "Wrote {out}/orphaned_behaviors.json ({} orphaned behaviors)",
+        orphaned.len()
+    );
+    for behavior in &orphaned {
+        println!(
+            "  {} [{}] {}",
+            behavior.steel_behavior,
+            behavior.kind,
+            behavior.path.as_deref().unwrap_or_default()
+        );
     }
 
-    // Write combined java.json
-    let java_json = serde_json::to_string_pretty(&all_java_classes).unwrap();
-    fs::write("outputs/java.json", java_json).unwrap();
+    // Vanilla classes explicitly claimed by several Steel types
+    write_json(
+        &format!("{out}/claim_conflicts.json"),
+        &result.claim_conflicts,
+    );
     println!(
-        "Wrote outputs/java.json ({} classes)",
-        all_java_classes.len()
+        "Wrote {out}/claim_conflicts.json ({} classes claimed more than once)",
+        result.claim_conflicts.len()
     );
+    for conflict in &result.claim_conflicts {
+        println!(
+            "  {} claimed by {}, tracking {}",
+            conflict.vanilla_class,
+            conflict.steel_types.join(", "),
+            conflict.chosen
+        );
+    }
 
-    // Parse all Rust classes
-    let mut all_rust_classes: Vec<ClassMethods> = Vec::new();
+    // Low-confidence name matches are not used for tracking until confirmed
+    write_json(&format!("{out}/match_review.json"), &result.match_reviews);
+    println!(
+        "Wrote {out}/match_review.json ({} matches flagged for review)",
+        result.match_reviews.len()
+    );
 
-    let block_rust = parse_rust_files_unfiltered("sources/SteelMC/steel-core/src/behavior/blocks");
-    for mut c in block_rust {
-        c.class_type = "block".to_string();
-        all_rust_classes.push(c);
-    }
+    let registry_report = analysis::registry_report(config, &sources);
+    write_json(&format!("{out}/registry.json"), &registry_report);
+    println!(
+        "Wrote {out}/registry.json ({} registry entries)",
+        registry_report.len()
+    );
 
-    let item_rust = parse_rust_files_unfiltered("sources/SteelMC/steel-core/src/behavior/items");
-    for mut c in item_rust {
-        c.class_type = "item".to_string();
-        all_rust_classes.push(c);
-    }
+    write_json(&format!("{out}/analysis.json"), &result);
+    println!(
+        "Wrote {out}/analysis.json ({} classes)",
+        result.classes.len()
+    );
 
-    let entity_rust = parse_rust_files_unfiltered("sources/SteelMC/steel-core/src/entity");
-    for mut c in entity_rust {
-        c.class_type = "entity".to_string();
-        all_rust_classes.push(c);
+    // Summary by type
+    println!("\n=== Summary by Type ===");
+    for summary in coverage::summarize(&result) {
+        let name = &summary.category;
+        if summary.classes > 0 {
+            print!(
+                "{}: {} classes, {:.1}% implemented ({:.1}% complexity-weighted",
+                name,
+                summary.classes,
+                summary.percentage_implemented,
+                summary.weighted_percentage_implemented
+            );
+            if let Some(usage) = summary.usage_weighted_percentage {
+                print!(", {:.1}% usage-weighted", usage);
+            }
+            println!(")");
+        }
+        if summary.ignored_methods > 0 {
+            println!(
+                "{}: {} methods intentionally omitted",
+                name, summary.ignored_methods
+            );
+        }
+        if summary.stale_methods > 0 {
+            println!(
+                "{}: {} implemented methods stale since verification",
+                name, summary.stale_methods
+            );
+        }
+        if summary.fallback_classes > 0 {
+            println!(
+                "{}: {} classes using generic fallback",
+                name, summary.fallback_classes
+            );
+        }
     }
 
-    // Build Rust lookup maps
-    let rust_map: HashMap<String, Vec<String>> = all_rust_classes
+    // Explicit vanilla markers in Steel source, reported apart from inferred matches
+    let count_classes = |source: MatchSource| {
+        result
+            .classes
+            .iter()
+            .filter(|c| c.class_matched_by == Some(source))
+            .count()
+    };
+    let count_methods = |source: MatchSource| {
+        result
+            .classes
+            .iter()
+            .flat_map(|c| &c.methods)
+            .filter(|m| m.matched_by == Some(source))
+            .count()
+    };
+    println!("\n=== Match Sources ===");
+    println!(
+        "classes: {} explicit, {} inferred",
+        count_classes(MatchSource::Explicit),
+        count_classes(MatchSource::Inferred)
+    );
+    println!(
+        "methods: {} explicit, {} inferred",
+        count_methods(MatchSource::Explicit),
+        count_methods(MatchSource::Inferred)
+    );
+
+    // Classes whose completion would cover the most registry entries
+    let mut wins: Vec<(&ClassTracking, f32)> = result
+        .classes
         .iter()
-        .map(|c| (c.class_name.to_lowercase(), c.methods.clone()))
+        .filter(|c| c.generic_fallback.is_none())
+        .map(|c| {
+            let usage = Coverage::usage_weighted(c);
+            (c, usage.total - usage.implemented)
+        })
+        .filter(|(_, missing)| *missing > 0.0)
         .collect();
-
-    // Method mappings: Java method -> Option<Rust method>
-    // None means Steel doesn't have this method yet (needs implementation)
-    let block_methods: HashMap<&str, Option<&str>> = [
-        // Steel has these
-        ("getStateForNeighborUpdate", Some("update_shape")),
-        ("getPlacementState", Some("get_state_for_placement")),
-        ("onBlockAdded", Some("on_place")),
-        ("onUseWithItem", Some("use_item_on")),
-        ("onUse", Some("use_without_item")),
-        ("neighborUpdate", Some("handle_neighbor_changed")),
-        ("randomTick", Some("random_tick")),
-        ("getPickStack", Some("get_clone_item_stack")),
-        ("hasRandomTicks", Some("is_randomly_ticking")),
-        ("createBlockEntity", Some("new_block_entity")),
-        ("hasComparatorOutput", Some("has_analog_output_signal")),
-        ("getComparatorOutput", Some("get_analog_output_signal")),
-        ("getFluidState", Some("get_fluid_state")),
-        // Steel doesn't have these yet
-        ("onStateReplaced", None),
-        ("onBlockBreakStart", None),
-        ("onBroken", None),
-        ("onDestroyedByExplosion", None),
-        ("onEntityCollision", None),
-        ("onProjectileHit", None),
-        ("onSteppedOn", None),
-        ("onLandedUpon", None),
-        ("scheduledTick", None),
-        ("getOutlineShape", None),
-        ("getCollisionShape", None),
-        ("getRaycastShape", None),
-        ("canPlaceAt", None),
-        ("canReplace", None),
-        ("rotate", None),
-        ("mirror", None),
-    ]
-    .into_iter()
-    .collect();
-
-    let item_methods: HashMap<&str, Option<&str>> = [
-        // Steel has these
-        ("useOnBlock", Some("use_on")),
-        // Steel doesn't have these yet
-        ("use", None),
-        ("usageTick", None),
-        ("finishUsing", None),
-        ("postHit", None),
-        ("postMine", None),
-        ("postDamageEntity", None),
-        ("useOnEntity", None),
-        ("inventoryTick", None),
-        ("onStoppedUsing", None),
-        ("getMaxUseTime", None),
-        ("getMiningSpeed", None),
-        ("onCraft", None),
-        ("onCraftByPlayer", None),
-    ]
-    .into_iter()
-    .collect();
-
-    let entity_methods: HashMap<&str, Option<&str>> = [
-        // Steel has these
-        ("tick", Some("tick")),
-        ("writeCustomDataToNbt", Some("save_additional")),
-        ("readCustomDataFromNbt", Some("load_additional")),
-        // Steel doesn't have these yet
-        ("damage", None),
-        ("onDeath", None),
-        ("onKilledOther", None),
-        ("interact", None),
-        ("interactAt", None),
-        ("onSpawnPacket", None),
-        ("onStruckByLightning", None),
-        ("pushAwayFrom", None),
-        ("travel", None),
-        ("tickMovement", None),
-        ("mobTick", None),
-        ("getActiveEyeHeight", None),
-    ]
-    .into_iter()
-    .collect();
-
-    let goal_methods: HashMap<&str, Option<&str>> = [
-        // Steel has these (placeholder - Steel doesn't have AI yet)
-        ("canStart", None),
-        ("shouldContinue", None),
-        ("start", None),
-        ("stop", None),
-        ("tick", None),
-        ("canStop", None),
-        ("shouldRunEveryTick", None),
-    ]
-    .into_iter()
-    .collect();
-
-    // Warn about methods Steel doesn't have yet
-    println!("\n=== Methods Steel Needs to Implement ===");
-    let mut missing_methods: Vec<(&str, &str)> = Vec::new();
-    for (java_method, rust_method) in &block_methods {
-        if rust_method.is_none() {
-            missing_methods.push(("Block", java_method));
+    wins.sort_by(|a, b| {
+        b.1.total_cmp(&a.1)
+            .then(a.0.class_name.cmp(&b.0.class_name))
+    });
+    if !wins.is_empty() {
+        println!("\n=== Biggest Wins by Registry Usage ===");
+        for (class, missing) in wins.iter().take(10) {
+            println!(
+                "  {} ({} entries, {:.1}% implemented, {:.1} entries outstanding)",
+                class.class_name, class.registry_usage, class.percentage_implemented, missing
+            );
         }
     }
-    for (java_method, rust_method) in &item_methods {
-        if rust_method.is_none() {
-            missing_methods.push(("Item", java_method));
-        }
+}
+
+/// `history --steel-repo <repo> --range <A..B>`: Steel coverage at every commit of a range
+fn run_history(mut config: Config, mut args: Vec<String>) {
+    if let Some(repo) = take_value(&mut args, "--steel-repo") {
+        config.steel_core = format!("{repo}/steel-core");
     }
-    for (java_method, rust_method) in &entity_methods {
-        if rust_method.is_none() {
-            missing_methods.push(("Entity", java_method));
+    let range = take_value(&mut args, "--range")
+        .unwrap_or_else(|| usage_error("history needs --range <A..B>"));
+    apply_source_args(&mut config, args);
+    let out = &config.output_dir;
+
+    fs::create_dir_all(format!("{out}/history")).expect("Failed to create history directory");
+    let history = history::backfill(&config, &range, |commit, result| {
+        write_json(&format!("{out}/history/{}.json", commit.hash), result);
+    })
+    .unwrap_or_else(|e| exit_with(e));
+
+    write_json(&format!("{out}/history.json"), &history);
+    println!(
+        "Wrote {out}/history.json and {out}/history/ ({} commits)",
+        history.series.len()
+    );
+    println!("\n=== Coverage by Commit ===");
+    for point in &history.series {
+        let commit = &point.commit;
+        println!(
+            "  {} {} {:.1}% ({}/{} methods) {}",
+            &commit.hash[..commit.hash.len().min(10)],
+            commit.date,
+            point.percentage_implemented,
+            point.implemented_methods,
+            point.tracked_methods,
+            commit.subject
+        );
+        if !point.generated_from_working_tree.is_empty() {
+            println!(
+                "    registrations from the working tree, not this commit: {}",
+                point.generated_from_working_tree.join(", ")
+            );
         }
     }
-    for (java_method, rust_method) in &goal_methods {
-        if rust_method.is_none() {
-            missing_methods.push(("Goal", java_method));
-        }
+    println!(
+        "{} methods first implemented in {range}",
+        history.first_implemented.len()
+    );
+}
+
+/// `behavior <SteelType>`: every vanilla class and registry id a Steel behavior stands in for
+fn run_behavior(mut config: Config, mut args: Vec<String>) {
+    if args.is_empty() || args[0].starts_with("--") {
+        usage_error("behavior needs a Steel type name");
     }
-    for (type_name, method) in &missing_methods {
-        println!("  {}: {} (not in Steel yet)", type_name, method);
+    let behavior = args.remove(0);
+    apply_source_args(&mut config, args);
+    let out = &config.output_dir;
+
+    let sources = analysis::load_sources(&config).unwrap_or_else(|e| exit_with(e));
+    let mappings = analysis::build_mappings(&config, &sources);
+    let result = analysis::compute_tracking(&config, &sources, &mappings);
+    let usage = lookup::behavior_usage(&config, &sources, &result, &behavior);
+    if usage.vanilla_classes.is_empty() && usage.unknown_registry_ids.is_empty() {
+        println!("No vanilla classes or registry ids map to {behavior}");
+        return;
     }
 
-    // Analyze each Java class
-    let mut tracking: Vec<ClassTracking> = Vec::new();
-
-    for java_class in &all_java_classes {
-        if !java_class.is_real_class || java_class.methods.is_empty() {
-            continue;
+    let behavior = &usage.steel_behavior;
+    write_json(&format!("{out}/behavior_{behavior}.json"), &usage);
+    println!(
+        "{behavior}: {} vanilla classes, {} registry ids",
+        usage.vanilla_classes.len(),
+        usage.registry_ids()
+    );
+    let percentage = |p: Option<f32>| p.map_or("untracked".to_string(), |p| format!("{p:.1}%"));
+    println!(
+        "  {} implemented, {} usage-weighted",
+        percentage(usage.percentage_implemented),
+        percentage(usage.usage_weighted_percentage)
+    );
+    for class in &usage.vanilla_classes {
+        println!(
+            "\n  {} [{}] {}/{} methods ({})",
+            class.vanilla_class,
+            class.kind,
+            class.implemented_methods,
+            class.tracked_methods,
+            percentage(class.percentage_implemented)
+        );
+        if !class.registry_ids.is_empty() {
+            println!("    {}", class.registry_ids.join(", "));
         }
+    }
+    if !usage.unknown_registry_ids.is_empty() {
+        println!(
+            "\n  Unknown to classes.json: {}",
+            usage.unknown_registry_ids.join(", ")
+        );
+    }
+}
 
-        // Get method mapping based on type
-        let method_map: &HashMap<&str, Option<&str>> = match java_class.class_type.as_str() {
-            "block" => &block_methods,
-            "item" => &item_methods,
-            "entity" => &entity_methods,
-            "ai_goal" => &goal_methods,
-            "ai_brain" => &goal_methods,
-            _ => &entity_methods,
-        };
+/// `show <Class> [--width <columns>]`: vanilla Java and Steel Rust of every tracked method,
+/// side by side
+fn run_show(mut config: Config, mut args: Vec<String>) {
+    let width = take_value(&mut args, "--width")
+        .map(|w| {
+            w.parse()
+                .unwrap_or_else(|_| usage_error("--width needs a number"))
+        })
+        .unwrap_or(60);
+    if args.is_empty() || args[0].starts_with("--") {
+        usage_error("show needs a vanilla class name");
+    }
+    let class_name = args.remove(0);
+    apply_source_args(&mut config, args);
 
-        // Only include classes that have at least one tracked method
-        let has_tracked_method = java_class
-            .methods
-            .iter()
-            .any(|m| method_map.contains_key(m.as_str()));
-        if !has_tracked_method {
-            continue;
-        }
+    let sources = analysis::load_sources(&config).unwrap_or_else(|e| exit_with(e));
+    let mappings = analysis::build_mappings(&config, &sources);
+    let result = analysis::compute_tracking(&config, &sources, &mappings);
+    let (class, methods) = show::method_sources(&config, &sources, &result, &class_name)
+        .unwrap_or_else(|e| exit_with(e));
 
-        // Find corresponding Rust class
-        let rust_methods = if java_class.class_type == "block" {
-            block_class_map
-                .get(&java_class.class_name)
-                .and_then(|steel| rust_map.get(&steel.to_lowercase()))
-                .cloned()
-                .unwrap_or_default()
-        } else if java_class.class_type == "item" {
-            item_class_map
-                .get(&java_class.class_name)
-                .and_then(|steel| rust_map.get(&steel.to_lowercase()))
-                .cloned()
-                .unwrap_or_default()
-        } else {
-            // Direct name matching for entities/goals
-            rust_map
-                .get(&java_class.class_name.to_lowercase())
-                .cloned()
-                .unwrap_or_default()
+    println!(
+        "{} ({}) -> {}, {:.1}% implemented",
+        class.class_name,
+        class.class_type,
+        class.rust_class.as_deref().unwrap_or("no Steel type"),
+        class.percentage_implemented
+    );
+    for method in methods {
+        println!("\n--- {} ({:?}) ---", method.method_name, method.status);
+        let java = match method.java.is_empty() {
+            true => "missing".to_string(),
+            false => method.java.join("\n\n"),
         };
-
-        // Track method implementation status (only for tracked methods)
-        let mut method_tracking = Vec::new();
-        for java_method in &java_class.methods {
-            // Only track methods that are in our mapping
-            if let Some(rust_equiv_opt) = method_map.get(java_method.as_str()) {
-                let status = if let Some(rust_method) = rust_equiv_opt {
-                    // Steel has this method in its trait
-                    if rust_methods.contains(&rust_method.to_string()) {
-                        ImplementationStatus::Implemented
-                    } else {
-                        ImplementationStatus::NotImplemented
-                    }
-                } else {
-                    // Steel doesn't have this method yet
-                    ImplementationStatus::NotImplemented
-                };
-
-                method_tracking.push(MethodTracking {
-                    method_name: java_method.clone(),
-                    status,
-                });
-            }
-        }
-
-        let implemented = method_tracking
-            .iter()
-            .filter(|m| m.status == ImplementationStatus::Implemented)
-            .count();
-        let total = method_tracking.len();
-
-        tracking.push(ClassTracking {
-            class_name: java_class.class_name.clone(),
-            class_type: java_class.class_type.clone(),
-            methods: method_tracking,
-            percentage_implemented: if total > 0 {
-                (implemented as f32 / total as f32) * 100.0
-            } else {
-                0.0
-            },
-        });
+        let rust = match (&method.rust, &method.status) {
+            (Some(rust), _) => rust.clone(),
+            (None, ImplementationStatus::Ignored) => "ignored".to_string(),
+            (None, _) => "missing".to_string(),
+        };
+        print!("{}", show::side_by_side(&java, &rust, width));
     }
+}
 
-    tracking.sort_by(|a, b| a.class_name.cmp(&b.class_name));
+/// `verify [Class | Class.method]...`: record the current vanilla bodies of implemented methods
+/// as verified, all of them when no selector is given
+fn run_verify(mut config: Config, mut args: Vec<String>) {
+    let split = args
+        .iter()
+        .position(|a| a.starts_with("--"))
+        .unwrap_or(args.len());
+    let selectors: Vec<String> = args.drain(..split).collect();
+    apply_source_args(&mut config, args);
+
+    let result = analysis::analyze(&config).unwrap_or_else(|e| exit_with(e));
+    let mut lock = config.verified.clone();
+    let changed = lock.verify(&result, &selectors);
+    lock.save(&config.verified_path)
+        .unwrap_or_else(|e| panic!("Failed to write {}: {e}", config.verified_path));
+    println!(
+        "Wrote {} ({} fingerprints updated)",
+        config.verified_path, changed
+    );
+}
 
-    let result = AnalysisResult { classes: tracking };
+/// `upgrade --new-yarn <dir|jar> [--old-yarn <dir|jar>]`: what moving to a new Minecraft
+/// version changes for the methods Steel implements today
+fn run_upgrade(mut config: Config, mut args: Vec<String>) {
+    if let Some(old) = take_value(&mut args, "--old-yarn") {
+        config.yarn_src = old;
+    }
+    let new_yarn = take_value(&mut args, "--new-yarn")
+        .unwrap_or_else(|| usage_error("upgrade needs --new-yarn <dir|jar>"));
+    apply_source_args(&mut config, args);
+    let out = &config.output_dir;
+
+    let result = analysis::analyze(&config).unwrap_or_else(|e| exit_with(e));
+    let report = config
+        .yarn_provider()
+        .and_then(|old| {
+            let new = provider::open(&new_yarn)?;
+            upgrade::upgrade_report(&config, old.as_ref(), new.as_ref(), &result)
+        })
+        .unwrap_or_else(|e| exit_with(e));
+
+    write_json(&format!("{out}/upgrade.json"), &report);
+    let diff = &report.diff;
+    println!("Wrote {out}/upgrade.json");
+    println!(
+        "  Classes: {} added, {} removed, {} renamed, {} with method changes",
+        diff.classes_added.len(),
+        diff.classes_removed.len(),
+        diff.classes_renamed.len(),
+        diff.class_changes.len()
+    );
 
-    let analysis_json = serde_json::to_string_pretty(&result).unwrap();
-    fs::write("outputs/analysis.json", analysis_json).unwrap();
     println!(
-        "Wrote outputs/analysis.json ({} classes)",
-        result.classes.len()
+        "\n=== Implemented Methods Affected ({}) ===",
+        report.affected_claims.len()
     );
+    for claim in &report.affected_claims {
+        let change = match &claim.change {
+            ClaimChange::ClassRemoved => "class removed".to_string(),
+            ClaimChange::ClassRenamed { to } => format!("class renamed to {to}"),
+            ClaimChange::MethodRemoved => "method removed".to_string(),
+            ClaimChange::MethodRenamed { to } => format!("method renamed to {to}"),
+            ClaimChange::MethodChanged => "method body changed".to_string(),
+        };
+        println!(
+            "  [{}] {}.{}: {}",
+            claim.class_type, claim.class_name, claim.method_name, change
+        );
+    }
+}
 
-    // Summary by type
-    println!("\n=== Summary by Type ===");
-    for type_name in [
-        "block",
-        "item",
-        "entity",
-        "ai_goal",
-        "ai_brain",
-        "ai_control",
-        "ai_pathing",
-        "other",
-    ] {
-        let type_classes: Vec<_> = result
-            .classes
-            .iter()
-            .filter(|c| c.class_type == type_name)
-            .collect();
-        if !type_classes.is_empty() {
-            let total_methods: usize = type_classes.iter().map(|c| c.methods.len()).sum();
-            let impl_methods: usize = type_classes
-                .iter()
-                .map(|c| {
-                    c.methods
-                        .iter()
-                        .filter(|m| m.status == ImplementationStatus::Implemented)
-                        .count()
-                })
-                .sum();
-            let pct = if total_methods > 0 {
-                (impl_methods as f32 / total_methods as f32) * 100.0
-            } else {
-                0.0
-            };
-            println!(
-                "{}: {} classes, {:.1}% implemented",
-                type_name,
-                type_classes.len(),
-                pct
-            );
+/// `changelog --before <analysis.json> --after <analysis.json>`, or
+/// `changelog --steel-repo <repo> --range <A..B>`: release notes of parity changes
+fn run_changelog(mut config: Config, mut args: Vec<String>) {
+    if let Some(repo) = take_value(&mut args, "--steel-repo") {
+        config.steel_core = format!("{repo}/steel-core");
+    }
+    let before = take_value(&mut args, "--before");
+    let after = take_value(&mut args, "--after");
+    let range = take_value(&mut args, "--range");
+    apply_source_args(&mut config, args);
+    let out = &config.output_dir;
+
+    let changelog = match (before, after, range) {
+        (Some(before), Some(after), None) => {
+            changelog::diff(&load_snapshot(&before), &load_snapshot(&after))
+        }
+        (None, None, Some(range)) => {
+            changelog::from_range(&config, &range).unwrap_or_else(|e| exit_with(e))
         }
+        _ => usage_error("changelog needs either --before and --after, or --range <A..B>"),
+    };
+
+    let markdown = changelog.to_markdown();
+    let path = format!("{out}/changelog.md");
+    fs::write(&path, &markdown).unwrap_or_else(|e| panic!("Failed to write {path}: {e}"));
+    print!("{markdown}");
+}
+
+fn load_snapshot(path: &str) -> AnalysisResult {
+    fs::read_to_string(path)
+        .map_err(|e| Error::load(path, e.into()))
+        .and_then(|json| serde_json::from_str(&json).map_err(|e| Error::load(path, e.into())))
+        .unwrap_or_else(|e| exit_with(e))
+}
+
+/// `--yarn-src <dir|jar>`, `--steel-core <dir>` and `--steel-rev <revision>`
+fn apply_source_args(config: &mut Config, args: Vec<String>) {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let target = match arg.as_str() {
+            "--yarn-src" => &mut config.yarn_src,
+            "--steel-core" => &mut config.steel_core,
+            "--steel-rev" => config.steel_revision.insert(String::new()),
+            _ => usage_error(&format!("unknown argument {arg}")),
+        };
+        *target = args
+            .next()
+            .unwrap_or_else(|| usage_error(&format!("{arg} needs a value")));
     }
 }
 
-fn detect_entity_subtype(class_name: &str) -> String {
-    if class_name.ends_with("Goal") {
-        "ai_goal".to_string()
-    } else if class_name.ends_with("Task")
-        || class_name.ends_with("Sensor")
-        || class_name.ends_with("Memory")
-    {
-        "ai_brain".to_string()
-    } else if class_name.ends_with("Control")
-        || class_name.ends_with("LookControl")
-        || class_name.ends_with("MoveControl")
-    {
-        "ai_control".to_string()
-    } else if class_name.ends_with("Navigation") || class_name.ends_with("PathNodeMaker") {
-        "ai_pathing".to_string()
-    } else if class_name.ends_with("Entity") {
-        "entity".to_string()
-    } else {
-        "other".to_string()
+/// Remove a boolean `flag` from `args`, reporting whether it was there
+fn take_flag(args: &mut Vec<String>, flag: &str) -> bool {
+    let before = args.len();
+    args.retain(|a| a != flag);
+    args.len() != before
+}
+
+/// Remove `flag` and its value from `args`
+fn take_value(args: &mut Vec<String>, flag: &str) -> Option<String> {
+    let index = args.iter().position(|a| a == flag)?;
+    if index + 1 >= args.len() {
+        usage_error(&format!("{flag} needs a value"));
     }
+    args.remove(index);
+    Some(args.remove(index))
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("error: {message}");
+    std::process::exit(2);
+}
+
+fn write_json<T: Serialize + ?Sized>(path: &str, value: &T) {
+    let json = serde_json::to_string_pretty(value).unwrap();
+    fs::write(path, json).unwrap_or_else(|e| panic!("Failed to write {path}: {e}"));
+}
+
+fn exit_with(error: Error) -> ! {
+    eprintln!("error: {error}");
+    std::process::exit(1);
 }
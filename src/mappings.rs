@@ -0,0 +1,226 @@
+//! Vanilla method -> Steel method tables, and which table each class category is judged by.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Java method -> Option<Rust method>.
+/// None means Steel doesn't have this method yet (needs implementation)
+pub type MethodTable = BTreeMap<String, Option<String>>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MethodTables {
+    /// Table name -> method table
+    pub tables: BTreeMap<String, MethodTable>,
+    /// Class category -> table name. Categories without an entry are not tracked.
+    pub categories: HashMap<String, String>,
+}
+
+impl MethodTables {
+    pub fn for_category(&self, category: &str) -> Option<&MethodTable> {
+        self.categories
+            .get(category)
+            .and_then(|table| self.tables.get(table))
+    }
+}
+
+fn table(entries: &[(&str, Option<&str>)]) -> MethodTable {
+    entries
+        .iter()
+        .map(|(java, rust)| (java.to_string(), rust.map(str::to_string)))
+        .collect()
+}
+
+impl Default for MethodTables {
+    fn default() -> Self {
+        let mut tables = BTreeMap::new();
+
+        tables.insert(
+            "block".to_string(),
+            table(&[
+                // Steel has these
+                ("getStateForNeighborUpdate", Some("update_shape")),
+                ("getPlacementState", Some("get_state_for_placement")),
+                ("onBlockAdded", Some("on_place")),
+                ("onUseWithItem", Some("use_item_on")),
+                ("onUse", Some("use_without_item")),
+                ("neighborUpdate", Some("handle_neighbor_changed")),
+                ("randomTick", Some("random_tick")),
+                ("getPickStack", Some("get_clone_item_stack")),
+                ("hasRandomTicks", Some("is_randomly_ticking")),
+                ("createBlockEntity", Some("new_block_entity")),
+                ("hasComparatorOutput", Some("has_analog_output_signal")),
+                ("getComparatorOutput", Some("get_analog_output_signal")),
+                ("getFluidState", Some("get_fluid_state")),
+                // Steel doesn't have these yet
+                ("onStateReplaced", None),
+                ("onBlockBreakStart", None),
+                ("onBroken", None),
+                ("onDestroyedByExplosion", None),
+                ("onEntityCollision", None),
+                ("onProjectileHit", None),
+                ("onSteppedOn", None),
+                ("onLandedUpon", None),
+                ("scheduledTick", None),
+                ("getOutlineShape", None),
+                ("getCollisionShape", None),
+                ("getRaycastShape", None),
+                ("canPlaceAt", None),
+                ("canReplace", None),
+                ("rotate", None),
+                ("mirror", None),
+            ]),
+        );
+
+        tables.insert(
+            "item".to_string(),
+            table(&[
+                // Steel has these
+                ("useOnBlock", Some("use_on")),
+                // Steel doesn't have these yet
+                ("use", None),
+                ("usageTick", None),
+                ("finishUsing", None),
+                ("postHit", None),
+                ("postMine", None),
+                ("postDamageEntity", None),
+                ("useOnEntity", None),
+                ("inventoryTick", None),
+                ("onStoppedUsing", None),
+                ("getMaxUseTime", None),
+                ("getMiningSpeed", None),
+                ("onCraft", None),
+                ("onCraftByPlayer", None),
+            ]),
+        );
+
+        tables.insert(
+            "entity".to_string(),
+            table(&[
+                // Steel has these
+                ("tick", Some("tick")),
+                ("writeCustomDataToNbt", Some("save_additional")),
+                ("readCustomDataFromNbt", Some("load_additional")),
+                // Steel doesn't have these yet
+                ("damage", None),
+                ("onDeath", None),
+                ("onKilledOther", None),
+                ("interact", None),
+                ("interactAt", None),
+                ("onSpawnPacket", None),
+                ("onStruckByLightning", None),
+                ("pushAwayFrom", None),
+                ("travel", None),
+                ("tickMovement", None),
+                ("mobTick", None),
+                ("getActiveEyeHeight", None),
+            ]),
+        );
+
+        tables.insert(
+            "block_entity".to_string(),
+            table(&[
+                // Steel has these
+                ("readNbt", Some("load_additional")),
+                ("writeNbt", Some("save_additional")),
+                // Steel doesn't have these yet
+                ("toUpdatePacket", None),
+                ("toInitialChunkDataNbt", None),
+                ("onBlockReplaced", None),
+                ("markRemoved", None),
+            ]),
+        );
+
+        // Steel doesn't have AI yet, so none of the AI tables have Steel equivalents
+        tables.insert(
+            "goal".to_string(),
+            table(&[
+                ("canStart", None),
+                ("shouldContinue", None),
+                ("start", None),
+                ("stop", None),
+                ("tick", None),
+                ("canStop", None),
+                ("shouldRunEveryTick", None),
+            ]),
+        );
+
+        tables.insert(
+            "brain_task".to_string(),
+            table(&[
+                ("shouldRun", None),
+                ("run", None),
+                ("shouldKeepRunning", None),
+                ("keepRunning", None),
+                ("finishRunning", None),
+                ("isTimeLimitExceeded", None),
+            ]),
+        );
+
+        tables.insert(
+            "brain_sensor".to_string(),
+            table(&[
+                ("sense", None),
+                ("getOutputMemoryModules", None),
+                ("tick", None),
+            ]),
+        );
+
+        // MoveControl, LookControl, JumpControl, BodyControl
+        tables.insert(
+            "control".to_string(),
+            table(&[
+                ("tick", None),
+                ("moveTo", None),
+                ("strafeTo", None),
+                ("isMoving", None),
+                ("lookAt", None),
+                ("setActive", None),
+            ]),
+        );
+
+        // EntityNavigation and PathNodeMaker
+        tables.insert(
+            "pathing".to_string(),
+            table(&[
+                ("findPathTo", None),
+                ("startMovingTo", None),
+                ("startMovingAlong", None),
+                ("tick", None),
+                ("stop", None),
+                ("isIdle", None),
+                ("createPathNodeNavigator", None),
+                ("isValidPosition", None),
+                ("init", None),
+                ("clear", None),
+                ("getStart", None),
+                ("getSuccessors", None),
+                ("getDefaultNodeType", None),
+                ("getNodeType", None),
+            ]),
+        );
+
+        let categories = [
+            ("block", "block"),
+            ("item", "item"),
+            ("entity", "entity"),
+            ("mob", "entity"),
+            ("projectile", "entity"),
+            ("vehicle", "entity"),
+            ("block_entity", "block_entity"),
+            ("ai_goal", "goal"),
+            ("ai_brain_task", "brain_task"),
+            ("ai_brain_sensor", "brain_sensor"),
+            ("ai_control", "control"),
+            ("ai_pathing", "pathing"),
+            // Unclassified classes keep the table they had before the AI tables existed. Other
+            // brain classes (`Brain`, `MemoryModuleType`) have no table of their own and stay
+            // untracked.
+            ("other", "entity"),
+        ]
+        .into_iter()
+        .map(|(category, table)| (category.to_string(), table.to_string()))
+        .collect();
+
+        Self { tables, categories }
+    }
+}
@@ -0,0 +1,218 @@
+//! Fuzzy matching of vanilla class names to Steel types, for classes not wired through a
+//! registry (entities, AI).
+
+use crate::utils;
+use crate::{NameMatch, NameMatchMethod};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Fields missing from a config file take their shipped value
+#[derive(Debug, Clone, Deserialize)]
+pub struct MatcherConfig {
+    /// Suffixes removed from both names before comparing (`ZombieEntity` -> `Zombie`)
+    #[serde(default = "shipped_strip_suffixes")]
+    pub strip_suffixes: Vec<String>,
+    /// Yarn class name -> Mojang class name
+    #[serde(default = "shipped_mojang_names")]
+    pub mojang_names: HashMap<String, String>,
+    /// Largest edit distance between stripped names still proposed as a match
+    #[serde(default = "shipped_max_edit_distance")]
+    pub max_edit_distance: usize,
+    /// Matches below this confidence are flagged for review instead of accepted
+    #[serde(default = "shipped_review_threshold")]
+    pub review_threshold: f32,
+}
+
+/// The settings shipped in `config/matching.json`
+impl Default for MatcherConfig {
+    fn default() -> Self {
+        serde_json::from_str(include_str!("../config/matching.json"))
+            .expect("config/matching.json is valid")
+    }
+}
+
+fn shipped_strip_suffixes() -> Vec<String> {
+    MatcherConfig::default().strip_suffixes
+}
+
+fn shipped_mojang_names() -> HashMap<String, String> {
+    MatcherConfig::default().mojang_names
+}
+
+fn shipped_max_edit_distance() -> usize {
+    MatcherConfig::default().max_edit_distance
+}
+
+fn shipped_review_threshold() -> f32 {
+    MatcherConfig::default().review_threshold
+}
+
+impl MatcherConfig {
+    /// Load the config, falling back to defaults when the file does not exist
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        utils::load_json_or_default(path)
+    }
+}
+
+/// A proposed match held back for manual review
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchReview {
+    pub java_class: String,
+    pub class_type: String,
+    pub candidate: NameMatch,
+}
+
+pub struct NameMatcher<'a> {
+    config: &'a MatcherConfig,
+    /// (original name, lowercase name, lowercase stripped name)
+    rust_names: Vec<(String, String, String)>,
+}
+
+impl<'a> NameMatcher<'a> {
+    pub fn new<'n>(config: &'a MatcherConfig, rust_classes: impl Iterator<Item = &'n str>) -> Self {
+        let mut rust_names: Vec<(String, String, String)> = rust_classes
+            .map(|name| {
+                let lower = name.to_lowercase();
+                let stripped = strip_suffixes(name, &config.strip_suffixes).to_lowercase();
+                (name.to_string(), lower, stripped)
+            })
+            .collect();
+        // Deterministic choice between equally good candidates
+        rust_names.sort();
+        Self { config, rust_names }
+    }
+
+    /// Best Steel candidate for a vanilla class, trying exact, Mojang, suffix-stripped and
+    /// edit-distance matches in decreasing order of confidence
+    pub fn best_match(&self, java_class: &str) -> Option<NameMatch> {
+        let candidate = |rust_class: &str, method: NameMatchMethod, confidence: f32| NameMatch {
+            rust_class: rust_class.to_string(),
+            method,
+            confidence,
+        };
+
+        let java_lower = java_class.to_lowercase();
+        if let Some((name, _, _)) = self
+            .rust_names
+            .iter()
+            .find(|(_, lower, _)| *lower == java_lower)
+        {
+            return Some(candidate(name, NameMatchMethod::Exact, 1.0));
+        }
+
+        let mojang = self.config.mojang_names.get(java_class);
+        if let Some(mojang) = mojang {
+            let mojang_lower = mojang.to_lowercase();
+            if let Some((name, _, _)) = self
+                .rust_names
+                .iter()
+                .find(|(_, lower, _)| *lower == mojang_lower)
+            {
+                return Some(candidate(name, NameMatchMethod::MojangName, 0.95));
+            }
+        }
+
+        let stripped: Vec<String> = std::iter::once(java_class)
+            .chain(mojang.map(String::as_str))
+            .map(|n| strip_suffixes(n, &self.config.strip_suffixes).to_lowercase())
+            .collect();
+        if let Some((name, _, _)) = self
+            .rust_names
+            .iter()
+            .find(|(_, _, rust_stripped)| stripped.contains(rust_stripped))
+        {
+            return Some(candidate(name, NameMatchMethod::SuffixStripped, 0.9));
+        }
+
+        let mut best: Option<(usize, usize, &str)> = None;
+        for (name, _, rust_stripped) in &self.rust_names {
+            for java_stripped in &stripped {
+                let distance = edit_distance(java_stripped, rust_stripped);
+                let len = java_stripped
+                    .chars()
+                    .count()
+                    .max(rust_stripped.chars().count());
+                if distance <= self.config.max_edit_distance
+                    && distance < len
+                    && best.is_none_or(|(d, _, _)| distance < d)
+                {
+                    best = Some((distance, len, name));
+                }
+            }
+        }
+        best.map(|(distance, len, name)| {
+            let confidence = 0.85 * (1.0 - distance as f32 / len as f32);
+            candidate(name, NameMatchMethod::EditDistance, confidence)
+        })
+    }
+
+    pub fn is_accepted(&self, candidate: &NameMatch) -> bool {
+        candidate.confidence >= self.config.review_threshold
+    }
+}
+
+fn strip_suffixes<'n>(name: &'n str, suffixes: &[String]) -> &'n str {
+    suffixes
+        .iter()
+        .filter_map(|suffix| name.strip_suffix(suffix.as_str()))
+        .find(|stripped| !stripped.is_empty())
+        .unwrap_or(name)
+}
+
+/// Levenshtein distance
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levenshtein() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("zombie", "zombie"), 0);
+        assert_eq!(edit_distance("zombie", "zombi"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn match_methods_in_order_of_confidence() {
+        let mut config = MatcherConfig::default();
+        config
+            .mojang_names
+            .insert("ItemEntity".to_string(), "ItemStackEntity".to_string());
+        let matcher = NameMatcher::new(
+            &config,
+            ["ZombieEntity", "Creeper", "ItemStackEntity", "Skeletn"].into_iter(),
+        );
+
+        let best = |name| matcher.best_match(name).unwrap();
+        assert_eq!(best("zombieentity").method, NameMatchMethod::Exact);
+        assert_eq!(best("ItemEntity").method, NameMatchMethod::MojangName);
+        assert_eq!(
+            best("CreeperEntity").method,
+            NameMatchMethod::SuffixStripped
+        );
+
+        let fuzzy = best("SkeletonEntity");
+        assert_eq!(
+            (fuzzy.rust_class.as_str(), fuzzy.method),
+            ("Skeletn", NameMatchMethod::EditDistance)
+        );
+        assert!(!matcher.is_accepted(&fuzzy));
+        assert!(matcher.is_accepted(&best("CreeperEntity")));
+        assert!(matcher.best_match("WardenEntity").is_none());
+    }
+}
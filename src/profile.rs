@@ -0,0 +1,146 @@
+//! Per-Minecraft-version overlays of the method tables and ignore list.
+//!
+//! A profile lives at `<config_dir>/profiles/<name>.json` and may `extend` another profile,
+//! which is applied first:
+//!
+//! ```json
+//! {
+//!   "extends": "1.21.4",
+//!   "yarn_src": "sources/yarn-25w10a/build/namedSrc",
+//!   "renames": { "onUse": "onUseWithoutItem" },
+//!   "method_tables": { "block": { "onUseWithoutItem": "use_without_item" } },
+//!   "ignore": [{ "class": "*", "method": "onSyncedBlockEvent", "kind": "not_applicable", "reason": "..." }]
+//! }
+//! ```
+//!
+//! Verified fingerprints are kept per profile in `<config_dir>/profiles/<name>.verified.lock.json`
+//! so verifying under one version does not mark another version's methods stale.
+
+use crate::Error;
+use crate::config::Config;
+use crate::ignore::IgnoreEntry;
+use crate::mappings::MethodTable;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Profile {
+    /// Profile applied before this one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+    /// Yarn sources of this version, when not the default tree
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub yarn_src: Option<String>,
+    /// Vanilla method renamed in this version, old name -> new name. Applied to the tables and
+    /// ignore entries inherited so far.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub renames: BTreeMap<String, String>,
+    /// Entries merged into the named tables, replacing inherited ones for the same method
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub method_tables: BTreeMap<String, MethodTable>,
+    /// Entries added to the ignore list
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignore: Vec<IgnoreEntry>,
+}
+
+impl Profile {
+    pub fn load(config_dir: &str, name: &str) -> Result<Self, Error> {
+        let path = format!("{config_dir}/profiles/{name}.json");
+        let content = fs::read_to_string(&path).map_err(|e| Error::load(&path, e.into()))?;
+        serde_json::from_str(&content).map_err(|e| Error::load(&path, e.into()))
+    }
+
+    pub fn verified_path(config_dir: &str, name: &str) -> String {
+        format!("{config_dir}/profiles/{name}.verified.lock.json")
+    }
+
+    /// `name` and the profiles it extends with their names, base first
+    pub fn resolve(config_dir: &str, name: &str) -> Result<Vec<(String, Self)>, Error> {
+        let mut chain: Vec<(String, Self)> = Vec::new();
+        let mut next = Some(name.to_string());
+        while let Some(name) = next {
+            if chain.iter().any(|(seen, _)| *seen == name) {
+                let path = format!("{config_dir}/profiles/{name}.json");
+                return Err(Error::load(&path, "profile inheritance cycle".into()));
+            }
+            let profile = Self::load(config_dir, &name)?;
+            next = profile.extends.clone();
+            chain.push((name, profile));
+        }
+        chain.reverse();
+        Ok(chain)
+    }
+
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(yarn_src) = &self.yarn_src {
+            config.yarn_src = yarn_src.clone();
+        }
+
+        for (from, to) in &self.renames {
+            for table in config.method_tables.tables.values_mut() {
+                if let Some(rust) = table.remove(from) {
+                    table.insert(to.clone(), rust);
+                }
+            }
+            for entry in &mut config.ignore.entries {
+                if entry.method.as_ref() == Some(from) {
+                    entry.method = Some(to.clone());
+                }
+            }
+            config.verified.rename_method(from, to);
+        }
+
+        for (name, entries) in &self.method_tables {
+            let table = config.method_tables.tables.entry(name.clone()).or_default();
+            table.extend(entries.clone());
+        }
+        config.ignore.entries.extend(self.ignore.iter().cloned());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IgnoreKind;
+
+    #[test]
+    fn renames_carry_tables_ignores_and_verifications() {
+        let mut config = Config::default();
+        config.method_tables.tables.insert(
+            "block".to_string(),
+            MethodTable::from([("onUse".to_string(), Some("use_without_item".to_string()))]),
+        );
+        config.ignore.entries.push(IgnoreEntry {
+            class: "*".to_string(),
+            method: Some("onUse".to_string()),
+            kind: IgnoreKind::NotApplicable,
+            reason: "test".to_string(),
+        });
+        config.verified.classes.insert(
+            "BarrelBlock".to_string(),
+            BTreeMap::from([("onUse".to_string(), "00ff".to_string())]),
+        );
+
+        let profile = Profile {
+            renames: BTreeMap::from([("onUse".to_string(), "onUseWithoutItem".to_string())]),
+            ..Default::default()
+        };
+        profile.apply(&mut config);
+
+        let block = &config.method_tables.tables["block"];
+        assert!(block.contains_key("onUseWithoutItem") && !block.contains_key("onUse"));
+        assert!(
+            config
+                .ignore
+                .lookup("AnyBlock", "onUseWithoutItem")
+                .is_some()
+        );
+        assert!(config.ignore.lookup("AnyBlock", "onUse").is_none());
+        assert_eq!(
+            config.verified.get("BarrelBlock", "onUseWithoutItem"),
+            Some("00ff")
+        );
+        assert_eq!(config.verified.get("BarrelBlock", "onUse"), None);
+    }
+}
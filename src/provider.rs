@@ -0,0 +1,268 @@
+//! Where source files come from: a directory, a zip/jar archive, or a git revision.
+
+use crate::Error;
+use std::cell::RefCell;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use zip::ZipArchive;
+
+/// A tree of source files. Paths are `/`-separated and relative to the provider root.
+pub trait SourceProvider {
+    /// Contents of the file at `path`, if it exists and is UTF-8
+    fn read(&self, path: &str) -> Option<String>;
+
+    /// `(path, contents)` of every file under `dir` with the given extension
+    fn files(&self, dir: &str, extension: &str) -> Vec<(PathBuf, String)>;
+}
+
+/// Open `path` as an archive when it ends in `.jar` or `.zip`, otherwise as a directory
+pub fn open(path: &str) -> Result<Box<dyn SourceProvider>, Error> {
+    if path.ends_with(".jar") || path.ends_with(".zip") {
+        Ok(Box::new(ZipProvider::open(path)?))
+    } else {
+        Ok(Box::new(DirectoryProvider::new(path)))
+    }
+}
+
+/// Files on disk below `root`
+pub struct DirectoryProvider {
+    root: PathBuf,
+}
+
+impl DirectoryProvider {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+impl SourceProvider for DirectoryProvider {
+    fn read(&self, path: &str) -> Option<String> {
+        fs::read_to_string(self.root.join(path)).ok()
+    }
+
+    fn files(&self, dir: &str, extension: &str) -> Vec<(PathBuf, String)> {
+        let dir = self.root.join(dir);
+        crate::utils::files_with_extension(&dir.to_string_lossy(), extension)
+            .map(|(path, content)| {
+                let relative = path.strip_prefix(&self.root).unwrap_or(&path);
+                (relative.to_path_buf(), content)
+            })
+            .collect()
+    }
+}
+
+/// Entries of a zip or jar archive, such as a `namedSrc` sources jar
+pub struct ZipProvider {
+    archive: RefCell<ZipArchive<File>>,
+}
+
+impl ZipProvider {
+    pub fn open(path: &str) -> Result<Self, Error> {
+        let archive = File::open(path)
+            .map_err(|e| Error::load(path, e.into()))
+            .and_then(|file| ZipArchive::new(file).map_err(|e| Error::load(path, e.into())))?;
+        Ok(Self {
+            archive: RefCell::new(archive),
+        })
+    }
+}
+
+impl SourceProvider for ZipProvider {
+    fn read(&self, path: &str) -> Option<String> {
+        let mut archive = self.archive.borrow_mut();
+        let mut entry = archive.by_name(path).ok()?;
+        let mut content = String::new();
+        entry.read_to_string(&mut content).ok()?;
+        Some(content)
+    }
+
+    fn files(&self, dir: &str, extension: &str) -> Vec<(PathBuf, String)> {
+        let prefix = match dir.trim_end_matches('/') {
+            "" => String::new(),
+            dir => format!("{dir}/"),
+        };
+        let suffix = format!(".{extension}");
+        let names: Vec<String> = self
+            .archive
+            .borrow()
+            .file_names()
+            .filter(|name| name.starts_with(&prefix) && name.ends_with(&suffix))
+            .map(String::from)
+            .collect();
+        names
+            .into_iter()
+            .filter_map(|name| {
+                let content = self.read(&name)?;
+                Some((PathBuf::from(name), content))
+            })
+            .collect()
+    }
+}
+
+/// Files of a local git repository at a fixed commit, read from the object database without
+/// touching the working tree
+pub struct GitProvider {
+    repo: PathBuf,
+    commit: String,
+    /// Path of `repo` below the top of the git work tree, e.g. `steel-core/`
+    prefix: String,
+}
+
+impl GitProvider {
+    /// `path` may be any directory inside the repository; file paths are relative to it
+    pub fn new(path: impl Into<PathBuf>, revision: &str) -> Result<Self, Error> {
+        let repo = path.into();
+        let missing = || {
+            Error::MissingSource(format!(
+                "git revision {revision} in {}",
+                repo.to_string_lossy()
+            ))
+        };
+        let commit = git(
+            &repo,
+            &["rev-parse", "--verify", &format!("{revision}^{{commit}}")],
+        )
+        .ok_or_else(missing)?;
+        let prefix = git(&repo, &["rev-parse", "--show-prefix"]).ok_or_else(missing)?;
+        Ok(Self {
+            repo,
+            commit: commit.trim().to_string(),
+            prefix: prefix.trim().to_string(),
+        })
+    }
+
+    /// Contents of many blobs at once through `git cat-file --batch`
+    fn read_batch(&self, paths: &[String]) -> Vec<Option<String>> {
+        let Ok(mut child) = Command::new("git")
+            .arg("-C")
+            .arg(&self.repo)
+            .args([
+                "cat-file",
+                "--batch=%(objectname) %(objecttype) %(objectsize)",
+            ])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            return vec![None; paths.len()];
+        };
+
+        let requests: String = paths
+            .iter()
+            .map(|path| format!("{}:{}{}\n", self.commit, self.prefix, path))
+            .collect();
+        let mut stdin = child.stdin.take().unwrap();
+        // Written from another thread so a full stdout pipe cannot deadlock us
+        let writer = std::thread::spawn(move || stdin.write_all(requests.as_bytes()));
+        let mut output = Vec::new();
+        let _ = child.stdout.take().unwrap().read_to_end(&mut output);
+        let _ = writer.join();
+        let _ = child.wait();
+        parse_batch(&output, paths.len())
+    }
+}
+
+/// Split `git cat-file --batch` output into `count` blobs. Each is announced by a
+/// `<oid> <type> <size>` header; a missing object by `<name> missing`, where the name may
+/// contain spaces, so headers are parsed from the right.
+fn parse_batch(output: &[u8], count: usize) -> Vec<Option<String>> {
+    let mut blobs = Vec::with_capacity(count);
+    let mut rest = output;
+    while blobs.len() < count {
+        let Some(newline) = rest.iter().position(|&b| b == b'\n') else {
+            break;
+        };
+        let header = String::from_utf8_lossy(&rest[..newline]).to_string();
+        rest = &rest[newline + 1..];
+        let mut fields = header.rsplitn(3, ' ');
+        let (size, kind) = (fields.next(), fields.next());
+        match (kind, size.and_then(|s| s.parse::<usize>().ok())) {
+            (Some(kind), Some(size)) if size < rest.len() => {
+                let blob = &rest[..size];
+                rest = &rest[size + 1..];
+                let text = String::from_utf8(blob.to_vec()).ok();
+                blobs.push(text.filter(|_| kind == "blob"));
+            }
+            _ => blobs.push(None),
+        }
+    }
+    blobs.resize(count, None);
+    blobs
+}
+
+impl SourceProvider for GitProvider {
+    fn read(&self, path: &str) -> Option<String> {
+        self.read_batch(&[path.to_string()]).pop().flatten()
+    }
+
+    fn files(&self, dir: &str, extension: &str) -> Vec<(PathBuf, String)> {
+        let Some(listing) = git(
+            &self.repo,
+            &[
+                "ls-tree",
+                "-r",
+                "-z",
+                "--name-only",
+                &self.commit,
+                "--",
+                dir_or_root(dir),
+            ],
+        ) else {
+            return Vec::new();
+        };
+        let suffix = format!(".{extension}");
+        let paths: Vec<String> = listing
+            .split('\0')
+            .filter(|path| path.ends_with(&suffix))
+            .map(String::from)
+            .collect();
+        let blobs = self.read_batch(&paths);
+        paths
+            .into_iter()
+            .zip(blobs)
+            .filter_map(|(path, content)| Some((PathBuf::from(path), content?)))
+            .collect()
+    }
+}
+
+fn dir_or_root(dir: &str) -> &str {
+    if dir.is_empty() { "." } else { dir }
+}
+
+/// Stdout of a successful git command run in `dir`
+pub(crate) fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_output_with_missing_objects() {
+        let output = b"c0ffee blob 5\nhello\nabc:src/a b 12 missing\nbeef blob 0\n\n";
+        assert_eq!(
+            parse_batch(output, 3),
+            [Some("hello".to_string()), None, Some(String::new())]
+        );
+    }
+
+    #[test]
+    fn batch_skips_non_blobs_and_truncated_output() {
+        let output = b"aaaa tree 3\nxyz\nbbbb blob 10\nshort\n";
+        assert_eq!(parse_batch(output, 3), [None, None, None]);
+    }
+}
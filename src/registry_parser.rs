@@ -1,46 +1,130 @@
 //! Parser for classes.json and generated registration code.
 
 use regex::Regex;
-use serde::Deserialize;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ClassesJson {
     pub blocks: Vec<BlockEntry>,
     pub items: Vec<ItemEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entities: Vec<EntityEntry>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockEntry {
     pub name: String,
     pub class: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ItemEntry {
     pub name: String,
     pub class: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityEntry {
+    pub name: String,
+    pub class: String,
+}
+
 impl ClassesJson {
     pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
         let content = fs::read_to_string(path)?;
-        let classes: ClassesJson = serde_json::from_str(&content)?;
-        Ok(classes)
+        Ok(Self::from_source(&content)?)
+    }
+
+    pub fn from_source(content: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(content)
+    }
+}
+
+/// A registry id whose vanilla class differs between two registry sources
+#[derive(Debug, Clone, Serialize)]
+pub struct RegistryMismatch {
+    pub kind: String,
+    pub registry_id: String,
+    pub expected_class: Option<String>,
+    pub actual_class: Option<String>,
+}
+
+/// Compare two registry id -> vanilla class listings, e.g. classes.json against yarn-derived
+pub fn cross_check(expected: &ClassesJson, actual: &ClassesJson) -> Vec<RegistryMismatch> {
+    fn compare<'a>(
+        kind: &str,
+        expected: impl Iterator<Item = (&'a String, &'a String)>,
+        actual: impl Iterator<Item = (&'a String, &'a String)>,
+        mismatches: &mut Vec<RegistryMismatch>,
+    ) {
+        let expected: HashMap<&String, &String> = expected.collect();
+        let actual: HashMap<&String, &String> = actual.collect();
+        let names: HashSet<&String> = expected.keys().chain(actual.keys()).copied().collect();
+        for name in names {
+            let expected_class = expected.get(name).copied();
+            let actual_class = actual.get(name).copied();
+            if expected_class != actual_class {
+                mismatches.push(RegistryMismatch {
+                    kind: kind.to_string(),
+                    registry_id: namespaced(name),
+                    expected_class: expected_class.cloned(),
+                    actual_class: actual_class.cloned(),
+                });
+            }
+        }
+    }
+
+    let mut mismatches = Vec::new();
+    compare(
+        "block",
+        expected.blocks.iter().map(|b| (&b.name, &b.class)),
+        actual.blocks.iter().map(|b| (&b.name, &b.class)),
+        &mut mismatches,
+    );
+    compare(
+        "item",
+        expected.items.iter().map(|i| (&i.name, &i.class)),
+        actual.items.iter().map(|i| (&i.name, &i.class)),
+        &mut mismatches,
+    );
+    // classes.json carries no entities, so only compare them when both sides have some
+    if !expected.entities.is_empty() && !actual.entities.is_empty() {
+        compare(
+            "entity",
+            expected.entities.iter().map(|e| (&e.name, &e.class)),
+            actual.entities.iter().map(|e| (&e.name, &e.class)),
+            &mut mismatches,
+        );
+    }
+    mismatches.sort_by(|a, b| (&a.kind, &a.registry_id).cmp(&(&b.kind, &b.registry_id)));
+    mismatches
+}
+
+/// Registry id with the `minecraft:` namespace, e.g. `barrel` -> `minecraft:barrel`
+pub fn namespaced(name: &str) -> String {
+    if name.contains(':') {
+        name.to_string()
+    } else {
+        format!("minecraft:{name}")
     }
 }
 
 /// Parse generated blocks.rs to extract registry_id -> steel_behavior mapping
 pub fn parse_generated_blocks(path: &str) -> HashMap<String, String> {
-    let content = fs::read_to_string(path).unwrap_or_default();
+    generated_blocks_from_source(&fs::read_to_string(path).unwrap_or_default())
+}
+
+/// `parse_generated_blocks` on the contents of blocks.rs
+pub fn generated_blocks_from_source(content: &str) -> HashMap<String, String> {
     let mut map = HashMap::new();
 
     // Match patterns like: vanilla_blocks :: BARREL , Box :: new (BarrelBlock :: new
     let re =
         Regex::new(r"vanilla_blocks\s*::\s*(\w+)\s*,\s*Box\s*::\s*new\s*\(\s*(\w+)\s*::").unwrap();
 
-    for cap in re.captures_iter(&content) {
+    for cap in re.captures_iter(content) {
         let registry_id = cap[1].to_lowercase(); // BARREL -> barrel
         let steel_behavior = cap[2].to_string(); // BarrelBlock
         map.insert(registry_id, steel_behavior);
@@ -51,7 +135,11 @@ pub fn parse_generated_blocks(path: &str) -> HashMap<String, String> {
 
 /// Parse generated items.rs to extract registry_id -> steel_behavior mapping
 pub fn parse_generated_items(path: &str) -> HashMap<String, String> {
-    let content = fs::read_to_string(path).unwrap_or_default();
+    generated_items_from_source(&fs::read_to_string(path).unwrap_or_default())
+}
+
+/// `parse_generated_items` on the contents of items.rs
+pub fn generated_items_from_source(content: &str) -> HashMap<String, String> {
     let mut map = HashMap::new();
 
     // Match patterns like: vanilla_items :: ITEMS . stone , Box :: new (BlockItemBehavior :: new
@@ -59,7 +147,7 @@ pub fn parse_generated_items(path: &str) -> HashMap<String, String> {
         Regex::new(r"vanilla_items\s*::\s*ITEMS\s*\.\s*(\w+)\s*,\s*Box\s*::\s*new\s*\(\s*(\w+)")
             .unwrap();
 
-    for cap in re.captures_iter(&content) {
+    for cap in re.captures_iter(content) {
         let registry_id = cap[1].to_string(); // stone (already lowercase)
         let steel_behavior = cap[2].to_string(); // BlockItemBehavior
         map.insert(registry_id, steel_behavior);
@@ -68,30 +156,188 @@ pub fn parse_generated_items(path: &str) -> HashMap<String, String> {
     map
 }
 
-/// Combined mapping: vanilla_class -> steel_behavior (derived from registry IDs)
+/// Number of block and item registry entries backed by each vanilla class
+pub fn registry_usage_counts(classes_json: &ClassesJson) -> HashMap<String, usize> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    let classes = classes_json
+        .blocks
+        .iter()
+        .map(|b| &b.class)
+        .chain(classes_json.items.iter().map(|i| &i.class));
+    for class in classes {
+        *counts.entry(class.clone()).or_default() += 1;
+    }
+    counts
+}
+
+/// A classes.json entry with no Steel registration
+#[derive(Debug, Clone, Serialize)]
+pub struct UnregisteredEntry {
+    pub registry_id: String,
+    pub vanilla_class: String,
+}
+
+/// A Steel registration whose registry id is not in classes.json
+#[derive(Debug, Clone, Serialize)]
+pub struct UnknownRegistration {
+    pub registry_id: String,
+    pub steel_behavior: String,
+}
+
+/// Registry ids present on only one side of the classes.json / generated code join
+#[derive(Debug, Default, Serialize)]
+pub struct RegistrationGaps {
+    pub unregistered_blocks: Vec<UnregisteredEntry>,
+    pub unregistered_items: Vec<UnregisteredEntry>,
+    pub unknown_blocks: Vec<UnknownRegistration>,
+    pub unknown_items: Vec<UnknownRegistration>,
+}
+
+pub fn find_registration_gaps(
+    classes_json: &ClassesJson,
+    steel_blocks: &HashMap<String, String>,
+    steel_items: &HashMap<String, String>,
+) -> RegistrationGaps {
+    fn unregistered<'a>(
+        entries: impl Iterator<Item = (&'a String, &'a String)>,
+        steel: &HashMap<String, String>,
+    ) -> Vec<UnregisteredEntry> {
+        let mut missing: Vec<UnregisteredEntry> = entries
+            .filter(|(name, _)| !steel.contains_key(*name))
+            .map(|(name, class)| UnregisteredEntry {
+                registry_id: namespaced(name),
+                vanilla_class: class.clone(),
+            })
+            .collect();
+        missing.sort_by(|a, b| a.registry_id.cmp(&b.registry_id));
+        missing
+    }
+
+    fn unknown(steel: &HashMap<String, String>, known: &HashSet<&str>) -> Vec<UnknownRegistration> {
+        let mut unknown: Vec<UnknownRegistration> = steel
+            .iter()
+            .filter(|(name, _)| !known.contains(name.as_str()))
+            .map(|(name, behavior)| UnknownRegistration {
+                registry_id: namespaced(name),
+                steel_behavior: behavior.clone(),
+            })
+            .collect();
+        unknown.sort_by(|a, b| a.registry_id.cmp(&b.registry_id));
+        unknown
+    }
+
+    let block_names: HashSet<&str> = classes_json
+        .blocks
+        .iter()
+        .map(|b| b.name.as_str())
+        .collect();
+    let item_names: HashSet<&str> = classes_json.items.iter().map(|i| i.name.as_str()).collect();
+
+    RegistrationGaps {
+        unregistered_blocks: unregistered(
+            classes_json.blocks.iter().map(|b| (&b.name, &b.class)),
+            steel_blocks,
+        ),
+        unregistered_items: unregistered(
+            classes_json.items.iter().map(|i| (&i.name, &i.class)),
+            steel_items,
+        ),
+        unknown_blocks: unknown(steel_blocks, &block_names),
+        unknown_items: unknown(steel_items, &item_names),
+    }
+}
+
+/// A Steel behavior a vanilla class is wired to, with the registry ids backing it
+#[derive(Debug, Clone, Serialize)]
+pub struct BehaviorBinding {
+    pub steel_behavior: String,
+    pub registry_ids: Vec<String>,
+}
+
+/// vanilla_class -> Steel behaviors, the one backing most registry ids first
+pub type ClassMapping = HashMap<String, Vec<BehaviorBinding>>;
+
+/// Combined mapping: vanilla_class -> steel_behaviors (derived from registry IDs)
 pub fn build_class_mapping(
     classes_json: &ClassesJson,
     steel_blocks: &HashMap<String, String>,
     steel_items: &HashMap<String, String>,
-) -> (HashMap<String, String>, HashMap<String, String>) {
-    let mut block_mapping: HashMap<String, String> = HashMap::new();
-    let mut item_mapping: HashMap<String, String> = HashMap::new();
+) -> (ClassMapping, ClassMapping) {
+    fn bind(mapping: &mut ClassMapping, vanilla_class: &str, steel_behavior: &str, name: &str) {
+        let bindings = mapping.entry(vanilla_class.to_string()).or_default();
+        match bindings
+            .iter_mut()
+            .find(|b| b.steel_behavior == steel_behavior)
+        {
+            Some(binding) => binding.registry_ids.push(namespaced(name)),
+            None => bindings.push(BehaviorBinding {
+                steel_behavior: steel_behavior.to_string(),
+                registry_ids: vec![namespaced(name)],
+            }),
+        }
+    }
+
+    let mut block_mapping = ClassMapping::new();
+    let mut item_mapping = ClassMapping::new();
 
     // For blocks: classes.json tells us registry_id -> vanilla_class
     // steel_blocks tells us registry_id -> steel_behavior
-    // So we can derive vanilla_class -> steel_behavior
+    // So we can derive vanilla_class -> steel_behaviors
     for block in &classes_json.blocks {
         if let Some(steel_behavior) = steel_blocks.get(&block.name) {
-            block_mapping.insert(block.class.clone(), steel_behavior.clone());
+            bind(
+                &mut block_mapping,
+                &block.class,
+                steel_behavior,
+                &block.name,
+            );
         }
     }
 
     // Same for items
     for item in &classes_json.items {
         if let Some(steel_behavior) = steel_items.get(&item.name) {
-            item_mapping.insert(item.class.clone(), steel_behavior.clone());
+            bind(&mut item_mapping, &item.class, steel_behavior, &item.name);
         }
     }
 
+    for bindings in block_mapping.values_mut().chain(item_mapping.values_mut()) {
+        bindings.sort_by(|a, b| {
+            b.registry_ids
+                .len()
+                .cmp(&a.registry_ids.len())
+                .then_with(|| a.steel_behavior.cmp(&b.steel_behavior))
+        });
+    }
+
     (block_mapping, item_mapping)
 }
+
+/// A vanilla class whose registry ids are wired to more than one Steel behavior
+#[derive(Debug, Clone, Serialize)]
+pub struct MappingConflict {
+    pub vanilla_class: String,
+    pub kind: String,
+    pub bindings: Vec<BehaviorBinding>,
+}
+
+pub fn find_mapping_conflicts(
+    block_mapping: &ClassMapping,
+    item_mapping: &ClassMapping,
+) -> Vec<MappingConflict> {
+    let mut conflicts: Vec<MappingConflict> = [("block", block_mapping), ("item", item_mapping)]
+        .into_iter()
+        .flat_map(|(kind, mapping)| {
+            mapping
+                .iter()
+                .filter(|(_, bindings)| bindings.len() > 1)
+                .map(move |(vanilla_class, bindings)| MappingConflict {
+                    vanilla_class: vanilla_class.clone(),
+                    kind: kind.to_string(),
+                    bindings: bindings.clone(),
+                })
+        })
+        .collect();
+    conflicts.sort_by(|a, b| (&a.kind, &a.vanilla_class).cmp(&(&b.kind, &b.vanilla_class)));
+    conflicts
+}
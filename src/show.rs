@@ -0,0 +1,107 @@
+//! Vanilla Java next to the Steel Rust for each tracked method of a class.
+
+use crate::analysis::Sources;
+use crate::config::Config;
+use crate::provider::SourceProvider;
+use crate::{AnalysisResult, ClassTracking, Error, ImplementationStatus, SourceSpan};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Source text behind one tracked method
+#[derive(Debug, Clone)]
+pub struct MethodSource {
+    pub method_name: String,
+    pub status: ImplementationStatus,
+    /// One entry per overload
+    pub java: Vec<String>,
+    pub rust: Option<String>,
+}
+
+/// Sources of every tracked method of the vanilla class `class_name` (case-insensitive)
+pub fn method_sources<'a>(
+    config: &Config,
+    sources: &Sources,
+    result: &'a AnalysisResult,
+    class_name: &str,
+) -> Result<(&'a ClassTracking, Vec<MethodSource>), Error> {
+    let class = result
+        .classes
+        .iter()
+        .find(|c| c.class_name.eq_ignore_ascii_case(class_name))
+        .ok_or_else(|| Error::MissingSource(format!("no tracked vanilla class {class_name}")))?;
+    let java_class = sources
+        .java_classes
+        .iter()
+        .find(|c| c.class_name == class.class_name);
+
+    let yarn = config.yarn_provider()?;
+    let steel = config.steel_provider()?;
+    let mut files: HashMap<(bool, String), Option<String>> = HashMap::new();
+    let mut text = |is_java: bool, span: &SourceSpan| {
+        let provider: &dyn SourceProvider = if is_java {
+            yarn.as_ref()
+        } else {
+            steel.as_ref()
+        };
+        let content = files
+            .entry((is_java, span.path.clone()))
+            .or_insert_with(|| provider.read(&span.path));
+        content.as_deref().and_then(|c| span.text(c)).map(dedent)
+    };
+
+    let methods = class
+        .methods
+        .iter()
+        .map(|method| {
+            let java_spans = java_class
+                .and_then(|c| c.method_spans.get(&method.method_name))
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            MethodSource {
+                method_name: method.method_name.clone(),
+                status: method.status.clone(),
+                java: java_spans.iter().filter_map(|s| text(true, s)).collect(),
+                rust: method.rust_span.as_ref().and_then(|s| text(false, s)),
+            }
+        })
+        .collect();
+    Ok((class, methods))
+}
+
+/// Two columns of `width` characters each, long lines cut short
+pub fn side_by_side(left: &str, right: &str, width: usize) -> String {
+    let left: Vec<&str> = left.lines().collect();
+    let right: Vec<&str> = right.lines().collect();
+    let mut out = String::new();
+    for row in 0..left.len().max(right.len()) {
+        let cell = |lines: &[&str]| fit(lines.get(row).copied().unwrap_or_default(), width);
+        let line = format!("{:<width$} | {}", cell(&left), cell(&right));
+        let _ = writeln!(out, "{}", line.trim_end());
+    }
+    out
+}
+
+/// Tabs expanded and the common indentation removed
+fn dedent(text: &str) -> String {
+    let lines: Vec<String> = text.lines().map(|l| l.replace('\t', "    ")).collect();
+    let indent = lines
+        .iter()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|l| l.get(indent..).unwrap_or_default().trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn fit(line: &str, width: usize) -> String {
+    if line.chars().count() <= width {
+        return line.to_string();
+    }
+    let mut cut: String = line.chars().take(width.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
@@ -1,31 +1,211 @@
+use crate::matching::MatchReview;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ClassMethods {
     pub class_name: String,
     pub class_type: String,
     pub methods: Vec<String>,
     pub is_real_class: bool,
+    /// Package declared by the source file
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub package: String,
+    /// Class named in the `extends` clause
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub superclass: Option<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub method_metrics: HashMap<String, MethodMetrics>,
+    /// Fingerprint of each method's normalized body, combined over overloads
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub method_fingerprints: HashMap<String, String>,
+    /// Annotated `@Environment(EnvType.CLIENT)`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub client_only: bool,
+    /// Methods whose every overload is annotated `@Environment(EnvType.CLIENT)`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub client_only_methods: Vec<String>,
+    /// Vanilla classes this Steel type is explicitly marked as implementing
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub vanilla_classes: Vec<String>,
+    /// Explicitly marked vanilla method -> Steel function implementing it
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub vanilla_methods: HashMap<String, String>,
+    /// Where each method is defined, one span per overload or impl block
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub method_spans: HashMap<String, Vec<SourceSpan>>,
+}
+
+/// A struct declared in Steel source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RustStruct {
+    pub name: String,
+    /// Category of the folder it was found in
+    pub category: String,
+    pub span: SourceSpan,
+    /// Vanilla classes it is explicitly marked as implementing
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub vanilla_classes: Vec<String>,
+}
+
+/// A definition in a source file. Lines are 1-based and inclusive, bytes are a half-open range.
+/// `path` is relative to the root of the source provider it was read from.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SourceSpan {
+    pub path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    #[serde(default)]
+    pub start_byte: usize,
+    #[serde(default)]
+    pub end_byte: usize,
+}
+
+impl SourceSpan {
+    /// The spanned text of `content`, from the start of its first line so the indentation of
+    /// every line is kept
+    pub fn text<'a>(&self, content: &'a str) -> Option<&'a str> {
+        let start = content
+            .get(..self.start_byte)?
+            .rfind('\n')
+            .map_or(0, |i| i + 1);
+        content.get(start..self.end_byte)
+    }
+}
+
+/// Size metrics of a method body. Overloads of the same name are summed.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MethodMetrics {
+    pub statements: usize,
+    pub lines: usize,
+    pub cyclomatic: usize,
+}
+
+impl MethodMetrics {
+    pub fn add(&mut self, other: MethodMetrics) {
+        self.statements += other.statements;
+        self.lines += other.lines;
+        self.cyclomatic += other.cyclomatic;
+    }
+
+    /// Weight used for complexity-weighted coverage.
+    pub fn weight(&self) -> f32 {
+        self.cyclomatic.max(1) as f32
+    }
 }
 
 #[derive(Debug)]
 pub struct ClassInfo {
     pub name: String,
+    pub package: String,
     pub methods: Vec<String>,
     pub extends: Option<String>,
     pub implements: Vec<String>,
+    pub method_metrics: HashMap<String, MethodMetrics>,
+    /// method -> sum of the body fingerprints of its overloads
+    pub method_fingerprints: HashMap<String, u64>,
+    pub method_spans: HashMap<String, Vec<SourceSpan>>,
+    pub client_only: bool,
+    /// method -> whether all overloads seen so far are client-only
+    pub client_only_methods: HashMap<String, bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum ImplementationStatus {
     Implemented,
     NotImplemented,
+    /// Intentionally omitted, excluded from percentages
+    Ignored,
+    /// Implemented, but the vanilla body changed since the implementation was verified
+    Stale,
+}
+
+impl ImplementationStatus {
+    /// Steel has an implementation, verified or not
+    pub fn is_implemented(&self) -> bool {
+        matches!(self, Self::Implemented | Self::Stale)
+    }
+}
+
+/// How a vanilla class or method was matched to Steel code
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchSource {
+    /// Marked in Steel source with `/// vanilla: ...` or `#[vanilla(...)]`
+    Explicit,
+    /// Derived from registries, naming or the method tables
+    Inferred,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NameMatchMethod {
+    Exact,
+    MojangName,
+    SuffixStripped,
+    EditDistance,
+}
+
+/// A name-based match of a vanilla class to a Steel type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NameMatch {
+    pub rust_class: String,
+    pub method: NameMatchMethod,
+    pub confidence: f32,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IgnoreKind {
+    WontImplement,
+    NotApplicable,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IgnoreReason {
+    pub kind: IgnoreKind,
+    pub reason: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MethodTracking {
     pub method_name: String,
     pub status: ImplementationStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<MethodMetrics>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ignore_reason: Option<IgnoreReason>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matched_by: Option<MatchSource>,
+    /// Fingerprint of the vanilla body
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+    /// The Steel function implementing this method
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rust_span: Option<SourceSpan>,
+    /// The commit that last touched most lines of `rust_span`, from git blame. This is the
+    /// implementer unless the function was later rewritten by someone else.
+    #[serde(
+        default,
+        alias = "attribution",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub last_touched_by: Option<Attribution>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Attribution {
+    pub author: String,
+    pub email: String,
+    pub commit: String,
+    /// Unix timestamp of the authoring
+    pub author_time: i64,
+}
+
+impl MethodTracking {
+    pub fn weight(&self) -> f32 {
+        self.metrics.map(|m| m.weight()).unwrap_or(1.0)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,9 +214,62 @@ pub struct ClassTracking {
     pub class_type: String,
     pub methods: Vec<MethodTracking>,
     pub percentage_implemented: f32,
+    pub weighted_percentage_implemented: f32,
+    /// Number of block/item registry entries using this class
+    #[serde(default)]
+    pub registry_usage: usize,
+    /// Generic Steel behavior standing in for this class, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generic_fallback: Option<String>,
+    /// Steel type the class was judged against
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rust_class: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class_matched_by: Option<MatchSource>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name_match: Option<NameMatch>,
+    /// Other behaviors some of the class's registry ids are wired to, each judged on its own.
+    /// The class's percentages only count `rust_class`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub secondary_behaviors: Vec<SecondaryBehavior>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecondaryBehavior {
+    pub steel_behavior: String,
+    pub registry_ids: Vec<String>,
+    pub methods: Vec<MethodTracking>,
+    pub percentage_implemented: f32,
+}
+
+/// Parity of a single block or item registry entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryEntryReport {
+    pub registry_id: String,
+    pub kind: String,
+    pub vanilla_class: String,
+    pub steel_behavior: Option<String>,
+    pub registered: bool,
+    pub methods: Vec<MethodTracking>,
+    pub percentage_implemented: Option<f32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnalysisResult {
     pub classes: Vec<ClassTracking>,
+    /// Low-confidence name matches not used for tracking until confirmed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub match_reviews: Vec<MatchReview>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub claim_conflicts: Vec<ClaimConflict>,
+}
+
+/// A vanilla class explicitly marked as implemented by more than one Steel type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaimConflict {
+    pub vanilla_class: String,
+    /// Every claimant, sorted
+    pub steel_types: Vec<String>,
+    /// The claimant the class is tracked against
+    pub chosen: String,
 }
@@ -0,0 +1,371 @@
+//! What a Minecraft version bump changes for Steel: a diff of two yarn trees, cross-referenced
+//! with the methods Steel claims to implement.
+
+use crate::analysis;
+use crate::config::Config;
+use crate::provider::SourceProvider;
+use crate::{AnalysisResult, ClassMethods, Error};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Share of methods two classes must have in common to count as a rename
+const CLASS_RENAME_SIMILARITY: f32 = 0.6;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Rename {
+    pub from: String,
+    pub to: String,
+}
+
+/// Method changes within a class present in both versions (under its new name when renamed)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClassChanges {
+    pub class_name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub added: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub renamed: Vec<Rename>,
+    /// Same name, different body fingerprint
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changed: Vec<String>,
+}
+
+impl ClassChanges {
+    fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.renamed.is_empty()
+            && self.changed.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct YarnDiff {
+    pub classes_added: Vec<String>,
+    pub classes_removed: Vec<String>,
+    pub classes_renamed: Vec<Rename>,
+    pub class_changes: Vec<ClassChanges>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ClaimChange {
+    ClassRemoved,
+    ClassRenamed { to: String },
+    MethodRemoved,
+    MethodRenamed { to: String },
+    MethodChanged,
+}
+
+/// A method Steel implements whose vanilla counterpart moves or changes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AffectedClaim {
+    pub class_name: String,
+    pub class_type: String,
+    pub method_name: String,
+    pub change: ClaimChange,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpgradeReport {
+    pub diff: YarnDiff,
+    pub affected_claims: Vec<AffectedClaim>,
+}
+
+/// Diff the vanilla classes parsed from two yarn trees.
+///
+/// A removed and an added class of the same category are a rename when they share most of
+/// their methods; a removed and an added method are a rename when their body fingerprints match
+/// and no other candidate in the class has the same fingerprint.
+pub fn diff_classes(old: Vec<ClassMethods>, new: Vec<ClassMethods>) -> YarnDiff {
+    let by_name = |classes: Vec<ClassMethods>| -> BTreeMap<String, ClassMethods> {
+        classes
+            .into_iter()
+            .map(|c| (c.class_name.clone(), c))
+            .collect()
+    };
+    let old_classes = by_name(old);
+    let new_classes = by_name(new);
+
+    let mut removed: Vec<&ClassMethods> = old_classes
+        .values()
+        .filter(|c| !new_classes.contains_key(&c.class_name))
+        .collect();
+    let mut added: Vec<&ClassMethods> = new_classes
+        .values()
+        .filter(|c| !old_classes.contains_key(&c.class_name))
+        .collect();
+
+    // Best-scoring pairs first, each class used at most once
+    let mut candidates: Vec<(f32, &ClassMethods, &ClassMethods)> = Vec::new();
+    for old in &removed {
+        for new in &added {
+            let similarity = method_similarity(old, new);
+            if old.class_type == new.class_type && similarity >= CLASS_RENAME_SIMILARITY {
+                candidates.push((similarity, old, new));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| {
+        b.0.total_cmp(&a.0)
+            .then(a.1.class_name.cmp(&b.1.class_name))
+    });
+    let mut renamed = Vec::new();
+    let mut used: HashSet<&str> = HashSet::new();
+    for (_, old, new) in candidates {
+        if used.contains(old.class_name.as_str()) || used.contains(new.class_name.as_str()) {
+            continue;
+        }
+        used.insert(&old.class_name);
+        used.insert(&new.class_name);
+        renamed.push((old, new));
+    }
+    removed.retain(|c| !used.contains(c.class_name.as_str()));
+    added.retain(|c| !used.contains(c.class_name.as_str()));
+
+    let mut class_changes: Vec<ClassChanges> = old_classes
+        .values()
+        .filter_map(|old| Some((old, new_classes.get(&old.class_name)?)))
+        .chain(renamed.iter().copied())
+        .map(|(old, new)| method_changes(old, new))
+        .filter(|changes| !changes.is_empty())
+        .collect();
+    class_changes.sort_by(|a, b| a.class_name.cmp(&b.class_name));
+
+    let mut classes_renamed: Vec<Rename> = renamed
+        .iter()
+        .map(|(old, new)| Rename {
+            from: old.class_name.clone(),
+            to: new.class_name.clone(),
+        })
+        .collect();
+    classes_renamed.sort_by(|a, b| a.from.cmp(&b.from));
+
+    YarnDiff {
+        classes_added: added.iter().map(|c| c.class_name.clone()).collect(),
+        classes_removed: removed.iter().map(|c| c.class_name.clone()).collect(),
+        classes_renamed,
+        class_changes,
+    }
+}
+
+/// Implemented methods in `result` touched by `diff`
+pub fn affected_claims(diff: &YarnDiff, result: &AnalysisResult) -> Vec<AffectedClaim> {
+    let removed: HashSet<&str> = diff.classes_removed.iter().map(String::as_str).collect();
+    let renamed: HashMap<&str, &str> = diff
+        .classes_renamed
+        .iter()
+        .map(|r| (r.from.as_str(), r.to.as_str()))
+        .collect();
+    let changes: HashMap<&str, &ClassChanges> = diff
+        .class_changes
+        .iter()
+        .map(|c| (c.class_name.as_str(), c))
+        .collect();
+
+    let mut claims = Vec::new();
+    for class in &result.classes {
+        let name = class.class_name.as_str();
+        let class_changes = changes.get(renamed.get(name).copied().unwrap_or(name));
+        for method in &class.methods {
+            if !method.status.is_implemented() {
+                continue;
+            }
+            let method_name = method.method_name.as_str();
+            let change = if removed.contains(name) {
+                Some(ClaimChange::ClassRemoved)
+            } else if let Some(to) = renamed.get(name) {
+                Some(ClaimChange::ClassRenamed { to: to.to_string() })
+            } else {
+                class_changes.and_then(|c| method_change(c, method_name))
+            };
+            // A renamed class can also have changed methods, reported separately
+            let method_change = renamed
+                .contains_key(name)
+                .then(|| class_changes.and_then(|c| method_change(c, method_name)))
+                .flatten();
+
+            for change in change.into_iter().chain(method_change) {
+                claims.push(AffectedClaim {
+                    class_name: class.class_name.clone(),
+                    class_type: class.class_type.clone(),
+                    method_name: method.method_name.clone(),
+                    change,
+                });
+            }
+        }
+    }
+    claims.sort_by(|a, b| {
+        a.class_name
+            .cmp(&b.class_name)
+            .then(a.method_name.cmp(&b.method_name))
+    });
+    claims
+}
+
+/// Diff two yarn trees and cross-reference the Implemented claims of an analysis against `old`
+pub fn upgrade_report(
+    config: &Config,
+    old: &dyn SourceProvider,
+    new: &dyn SourceProvider,
+    result: &AnalysisResult,
+) -> Result<UpgradeReport, Error> {
+    // An empty tree on either side would make every class look added or removed
+    let parse = |yarn: &dyn SourceProvider, which: &str| {
+        let classes = analysis::parse_yarn_classes(config, yarn);
+        if classes.is_empty() {
+            return Err(Error::MissingSource(format!(
+                "no Java classes in the {which} yarn tree"
+            )));
+        }
+        Ok(classes)
+    };
+    let diff = diff_classes(parse(old, "old")?, parse(new, "new")?);
+    Ok(UpgradeReport {
+        affected_claims: affected_claims(&diff, result),
+        diff,
+    })
+}
+
+fn method_change(changes: &ClassChanges, method: &str) -> Option<ClaimChange> {
+    if changes.removed.iter().any(|m| m == method) {
+        Some(ClaimChange::MethodRemoved)
+    } else if let Some(rename) = changes.renamed.iter().find(|r| r.from == method) {
+        Some(ClaimChange::MethodRenamed {
+            to: rename.to.clone(),
+        })
+    } else if changes.changed.iter().any(|m| m == method) {
+        Some(ClaimChange::MethodChanged)
+    } else {
+        None
+    }
+}
+
+fn method_changes(old: &ClassMethods, new: &ClassMethods) -> ClassChanges {
+    let mut removed: Vec<String> = old
+        .methods
+        .iter()
+        .filter(|m| !new.methods.contains(m))
+        .cloned()
+        .collect();
+    let mut added: Vec<String> = new
+        .methods
+        .iter()
+        .filter(|m| !old.methods.contains(m))
+        .cloned()
+        .collect();
+    let mut changed: Vec<String> = old
+        .methods
+        .iter()
+        .filter(|m| {
+            new.methods.contains(m)
+                && old.method_fingerprints.get(*m) != new.method_fingerprints.get(*m)
+        })
+        .cloned()
+        .collect();
+
+    let fingerprint_count = |class: &ClassMethods, names: &[String], fingerprint: &String| {
+        names
+            .iter()
+            .filter(|m| class.method_fingerprints.get(*m) == Some(fingerprint))
+            .count()
+    };
+    let mut renamed = Vec::new();
+    for from in &removed {
+        let Some(fingerprint) = old.method_fingerprints.get(from) else {
+            continue;
+        };
+        if fingerprint_count(old, &removed, fingerprint) != 1
+            || fingerprint_count(new, &added, fingerprint) != 1
+        {
+            continue;
+        }
+        if let Some(to) = added
+            .iter()
+            .find(|m| new.method_fingerprints.get(*m) == Some(fingerprint))
+        {
+            renamed.push(Rename {
+                from: from.clone(),
+                to: to.clone(),
+            });
+        }
+    }
+    removed.retain(|m| !renamed.iter().any(|r| &r.from == m));
+    added.retain(|m| !renamed.iter().any(|r| &r.to == m));
+
+    removed.sort();
+    added.sort();
+    changed.sort();
+    renamed.sort_by(|a, b| a.from.cmp(&b.from));
+    ClassChanges {
+        class_name: new.class_name.clone(),
+        added,
+        removed,
+        renamed,
+        changed,
+    }
+}
+
+/// Shared methods over all distinct methods of the two classes
+fn method_similarity(a: &ClassMethods, b: &ClassMethods) -> f32 {
+    let a: HashSet<&String> = a.methods.iter().collect();
+    let b: HashSet<&String> = b.methods.iter().collect();
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f32 / union as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn class(methods: &[(&str, &str)]) -> ClassMethods {
+        ClassMethods {
+            class_name: "Foo".to_string(),
+            methods: methods.iter().map(|(m, _)| m.to_string()).collect(),
+            method_fingerprints: methods
+                .iter()
+                .map(|(m, fp)| (m.to_string(), fp.to_string()))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn changed_follows_fingerprints() {
+        let old = class(&[("tick", "a"), ("onUse", "b")]);
+        let new = class(&[("tick", "a"), ("onUse", "c")]);
+        let changes = method_changes(&old, &new);
+        assert_eq!(changes.changed, ["onUse"]);
+        assert!(changes.renamed.is_empty());
+    }
+
+    #[test]
+    fn unique_fingerprint_is_a_rename() {
+        let old = class(&[("onUse", "a"), ("tick", "b")]);
+        let new = class(&[("onUseWithoutItem", "a"), ("tick", "b")]);
+        let changes = method_changes(&old, &new);
+        assert_eq!(
+            changes.renamed,
+            [Rename {
+                from: "onUse".to_string(),
+                to: "onUseWithoutItem".to_string()
+            }]
+        );
+        assert!(changes.added.is_empty() && changes.removed.is_empty());
+    }
+
+    #[test]
+    fn ambiguous_fingerprints_are_not_renames() {
+        let old = class(&[("a", "x"), ("b", "x")]);
+        let new = class(&[("c", "x"), ("d", "x")]);
+        let changes = method_changes(&old, &new);
+        assert!(changes.renamed.is_empty());
+        assert_eq!(changes.removed, ["a", "b"]);
+        assert_eq!(changes.added, ["c", "d"]);
+    }
+}
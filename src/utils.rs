@@ -1,26 +1,65 @@
-use std::{fs, path::Path};
+use serde::de::DeserializeOwned;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 pub fn walk_files<F>(dir: &str, extension: &str, mut callback: F)
 where
     F: FnMut(&Path, String),
 {
-    fn walk_recursive<F>(dir: &Path, extension: &str, callback: &mut F)
-    where
-        F: FnMut(&Path, String),
-    {
-        if let Ok(entries) = fs::read_dir(dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.is_dir() {
-                    walk_recursive(&path, extension, callback);
-                } else if path.extension().and_then(|s| s.to_str()) == Some(extension)
-                    && let Ok(content) = fs::read_to_string(&path)
-                {
-                    callback(&path, content);
+    for (path, content) in files_with_extension(dir, extension) {
+        callback(&path, content);
+    }
+}
+
+/// Parse the JSON file at `path`, or `T::default()` when it does not exist
+pub fn load_json_or_default<T: DeserializeOwned + Default>(
+    path: &str,
+) -> Result<T, Box<dyn std::error::Error>> {
+    if !Path::new(path).exists() {
+        return Ok(T::default());
+    }
+    let content = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// 64-bit FNV-1a hash
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Lazily yields `(path, contents)` of every file under `dir` with the given extension
+pub fn files_with_extension(
+    dir: &str,
+    extension: &str,
+) -> impl Iterator<Item = (PathBuf, String)> + use<> {
+    let extension = extension.to_string();
+    let mut pending_dirs = vec![PathBuf::from(dir)];
+    let mut pending_files: Vec<PathBuf> = Vec::new();
+
+    std::iter::from_fn(move || {
+        loop {
+            while let Some(path) = pending_files.pop() {
+                if let Ok(content) = fs::read_to_string(&path) {
+                    return Some((path, content));
                 }
             }
-        }
-    }
 
-    walk_recursive(Path::new(dir), extension, &mut callback);
+            let dir = pending_dirs.pop()?;
+            if let Ok(entries) = fs::read_dir(&dir) {
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    if path.is_dir() {
+                        pending_dirs.push(path);
+                    } else if path.extension().and_then(|s| s.to_str()) == Some(extension.as_str())
+                    {
+                        pending_files.push(path);
+                    }
+                }
+            }
+        }
+    })
 }
@@ -0,0 +1,74 @@
+//! Lockfile of the vanilla method fingerprints Steel implementations were last verified against.
+
+use crate::AnalysisResult;
+use crate::utils;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VerifiedLock {
+    /// vanilla class -> method -> fingerprint of the verified body
+    pub classes: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl VerifiedLock {
+    /// Load the lockfile, treating a missing file as nothing verified yet
+    pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        utils::load_json_or_default(path)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        fs::write(path, serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+
+    /// Carry verifications of vanilla method `from` over to its new name `to`
+    pub fn rename_method(&mut self, from: &str, to: &str) {
+        for methods in self.classes.values_mut() {
+            if let Some(fingerprint) = methods.remove(from) {
+                methods.insert(to.to_string(), fingerprint);
+            }
+        }
+    }
+
+    pub fn get(&self, class: &str, method: &str) -> Option<&str> {
+        self.classes.get(class)?.get(method).map(String::as_str)
+    }
+
+    /// Mark the current vanilla body of every implemented (or stale) method in `result` as
+    /// verified. `only` restricts this to `Class` or `Class.method` selectors; empty means all.
+    /// Returns how many fingerprints changed.
+    pub fn verify(&mut self, result: &AnalysisResult, only: &[String]) -> usize {
+        let selected = |class: &str, method: &str| {
+            only.is_empty()
+                || only.iter().any(|s| match s.split_once('.') {
+                    Some((c, m)) => c == class && m == method,
+                    None => s == class,
+                })
+        };
+
+        let mut changed = 0;
+        for class in &result.classes {
+            for method in &class.methods {
+                let Some(fingerprint) = &method.fingerprint else {
+                    continue;
+                };
+                if !method.status.is_implemented()
+                    || !selected(&class.class_name, &method.method_name)
+                {
+                    continue;
+                }
+                let previous = self
+                    .classes
+                    .entry(class.class_name.clone())
+                    .or_default()
+                    .insert(method.method_name.clone(), fingerprint.clone());
+                if previous.as_ref() != Some(fingerprint) {
+                    changed += 1;
+                }
+            }
+        }
+        changed
+    }
+}